@@ -44,6 +44,18 @@ fn main() {
         );
         window.set_ime_position(cursor_position);
       }
+      Event::WindowEvent {
+        event: WindowEvent::ImePreedit(text, cursor_range),
+        ..
+      } => {
+        println!("Preedit: {text:?}, cursor: {cursor_range:?}");
+      }
+      Event::WindowEvent {
+        event: WindowEvent::ReceivedImeText(text),
+        ..
+      } => {
+        println!("Commit: {text:?}");
+      }
       Event::WindowEvent {
         event: WindowEvent::CloseRequested,
         ..
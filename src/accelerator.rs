@@ -0,0 +1,649 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `Accelerator` struct and associated types.
+//!
+//! An accelerator is a keyboard shortcut, written as modifiers followed by a key and separated by
+//! `+`, e.g. `"CmdOrCtrl+Shift+K"`. Names are case-insensitive. The modifiers are:
+//!
+//! - `Shift`
+//! - `Control` or `Ctrl`
+//! - `Alt` or `Option`
+//! - `Super`, `Command`, `Cmd` or `Meta`
+//! - `CmdOrCtrl`, `CommandOrControl`, `CmdOrControl` or `CommandOrCtrl`, which is `Super` on
+//!   macOS and `Control` elsewhere.
+//!
+//! The key is parsed with [`KeyCode`]'s [`FromStr`] implementation, e.g. `K`, `5`, `F12`,
+//! `Numpad0`, `ArrowUp` or `Backquote`.
+
+use std::{error, fmt, str::FromStr};
+
+use crate::{
+  event::{ElementState, KeyEvent},
+  keyboard::{KeyCode, ModifiersState},
+};
+
+/// A keyboard shortcut, made of modifiers and a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+  mods: ModifiersState,
+  key: KeyCode,
+}
+
+impl Accelerator {
+  /// Creates an accelerator, ignoring whether the left or right modifiers are set.
+  ///
+  /// Returns an error if `key` is [`KeyCode::Unidentified`].
+  pub fn new(mods: ModifiersState, key: KeyCode) -> Result<Self, AcceleratorParseError> {
+    if let KeyCode::Unidentified(_) = key {
+      return Err(AcceleratorParseError::UnknownKey("Unidentified".into()));
+    }
+    Ok(Self {
+      mods: combined_modifiers(mods),
+      key,
+    })
+  }
+
+  /// Returns the modifiers to hold, without the left and right flags.
+  pub fn mods(&self) -> ModifiersState {
+    self.mods
+  }
+
+  /// Returns the key to press.
+  pub fn key(&self) -> KeyCode {
+    self.key
+  }
+
+  /// Returns `true` if `event` is a press of the key of this accelerator while `modifiers` are
+  /// held. Releases never match.
+  ///
+  /// `modifiers` are usually tracked from [`WindowEvent::ModifiersChanged`] and must match
+  /// exactly, so `Ctrl+K` doesn't match when `Ctrl+Shift+K` is pressed. Either side of a modifier
+  /// matches.
+  ///
+  /// [`WindowEvent::ModifiersChanged`]: crate::event::WindowEvent::ModifiersChanged
+  pub fn matches(&self, event: &KeyEvent, modifiers: ModifiersState) -> bool {
+    event.state == ElementState::Pressed
+      && event.physical_key == self.key
+      && combined_modifiers(modifiers) == self.mods
+  }
+
+  /// Formats the accelerator following the platform conventions, to show it to users.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS:** Uses the modifier and key symbols, e.g. `⌃⌥⇧⌘K`.
+  /// - **Windows:** `Ctrl+Alt+Shift+Win+K`.
+  /// - **Linux / Android:** `Ctrl+Alt+Shift+Super+K`.
+  pub fn to_native_string(&self) -> String {
+    if cfg!(any(target_os = "macos", target_os = "ios")) {
+      let mut s = String::new();
+      for (flag, symbol) in [
+        (ModifiersState::CONTROL, "⌃"),
+        (ModifiersState::ALT, "⌥"),
+        (ModifiersState::SHIFT, "⇧"),
+        (ModifiersState::SUPER, "⌘"),
+      ] {
+        if self.mods.contains(flag) {
+          s.push_str(symbol);
+        }
+      }
+      s.push_str(&key_symbol(self.key).unwrap_or_else(|| key_name(self.key)));
+      s
+    } else {
+      let super_name = if cfg!(target_os = "windows") {
+        "Win"
+      } else {
+        "Super"
+      };
+      let mut parts = Vec::new();
+      for (flag, name) in [
+        (ModifiersState::CONTROL, "Ctrl"),
+        (ModifiersState::ALT, "Alt"),
+        (ModifiersState::SHIFT, "Shift"),
+        (ModifiersState::SUPER, super_name),
+      ] {
+        if self.mods.contains(flag) {
+          parts.push(name.to_string());
+        }
+      }
+      parts.push(key_name(self.key));
+      parts.join("+")
+    }
+  }
+}
+
+/// Formats the accelerator so it can be parsed back on any platform, e.g.
+/// `Control+Shift+K`. See [`Accelerator::to_native_string`] to show it to users.
+impl fmt::Display for Accelerator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (flag, name) in [
+      (ModifiersState::CONTROL, "Control"),
+      (ModifiersState::ALT, "Alt"),
+      (ModifiersState::SHIFT, "Shift"),
+      (ModifiersState::SUPER, "Super"),
+    ] {
+      if self.mods.contains(flag) {
+        write!(f, "{}+", name)?;
+      }
+    }
+    f.write_str(&key_name(self.key))
+  }
+}
+
+impl FromStr for Accelerator {
+  type Err = AcceleratorParseError;
+
+  fn from_str(accelerator: &str) -> Result<Self, Self::Err> {
+    if accelerator.trim().is_empty() {
+      return Err(AcceleratorParseError::Empty);
+    }
+
+    let mut mods = ModifiersState::empty();
+    let mut key = None;
+    for token in accelerator.split('+').map(str::trim) {
+      let modifier = match token.to_uppercase().as_str() {
+        "" => return Err(AcceleratorParseError::Empty),
+        "SHIFT" => ModifiersState::SHIFT,
+        "CONTROL" | "CTRL" => ModifiersState::CONTROL,
+        "ALT" | "OPTION" => ModifiersState::ALT,
+        "SUPER" | "COMMAND" | "CMD" | "META" => ModifiersState::SUPER,
+        "CMDORCTRL" | "COMMANDORCONTROL" | "CMDORCONTROL" | "COMMANDORCTRL" => {
+          if cfg!(any(target_os = "macos", target_os = "ios")) {
+            ModifiersState::SUPER
+          } else {
+            ModifiersState::CONTROL
+          }
+        }
+        _ => {
+          if key.is_some() {
+            return Err(AcceleratorParseError::MultipleKeys);
+          }
+          match KeyCode::from_str(token) {
+            Ok(KeyCode::Unidentified(_)) | Err(_) => {
+              return Err(AcceleratorParseError::UnknownKey(token.to_string()))
+            }
+            Ok(code) => key = Some(code),
+          }
+          continue;
+        }
+      };
+      mods |= modifier;
+    }
+
+    match key {
+      Some(key) => Ok(Self { mods, key }),
+      None => Err(AcceleratorParseError::MissingKey),
+    }
+  }
+}
+
+/// Identifies a global hotkey, returned when it's registered and sent with
+/// [`Event::GlobalHotKey`] when it's pressed.
+///
+/// [`Event::GlobalHotKey`]: crate::event::Event::GlobalHotKey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GlobalHotKeyId(pub(crate) u32);
+
+/// The error type for when an [`Accelerator`] can't be parsed.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceleratorParseError {
+  /// The accelerator, or one of its `+`-separated parts, is empty.
+  Empty,
+  /// The accelerator only has modifiers.
+  MissingKey,
+  /// The accelerator has more than one key.
+  MultipleKeys,
+  /// A part of the accelerator is neither a modifier nor a known key.
+  UnknownKey(String),
+}
+
+impl fmt::Display for AcceleratorParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AcceleratorParseError::Empty => f.pad("the accelerator has an empty part"),
+      AcceleratorParseError::MissingKey => f.pad("the accelerator has no key"),
+      AcceleratorParseError::MultipleKeys => f.pad("the accelerator has more than one key"),
+      AcceleratorParseError::UnknownKey(key) => write!(f, "unknown accelerator key {:?}", key),
+    }
+  }
+}
+
+impl error::Error for AcceleratorParseError {}
+
+#[cfg(feature = "serde")]
+mod accelerator_serde {
+  use std::str::FromStr;
+
+  use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+  use super::Accelerator;
+
+  impl Serialize for Accelerator {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+      S: Serializer,
+    {
+      serializer.collect_str(self)
+    }
+  }
+
+  impl<'de> Deserialize<'de> for Accelerator {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+      D: Deserializer<'de>,
+    {
+      let s = String::deserialize(deserializer)?;
+      Accelerator::from_str(&s).map_err(de::Error::custom)
+    }
+  }
+}
+
+/// Sets the combined flag of each modifier held on either side, and clears the side flags.
+fn combined_modifiers(mods: ModifiersState) -> ModifiersState {
+  let mut combined = ModifiersState::empty();
+  combined.set(ModifiersState::SHIFT, mods.shift_key());
+  combined.set(ModifiersState::CONTROL, mods.control_key());
+  combined.set(ModifiersState::ALT, mods.alt_key());
+  combined.set(ModifiersState::SUPER, mods.super_key());
+  combined
+}
+
+/// Returns the name of `key` that [`KeyCode::from_str`] parses back.
+fn key_name(key: KeyCode) -> String {
+  let name = key.to_string();
+  match key {
+    KeyCode::KeyA
+    | KeyCode::KeyB
+    | KeyCode::KeyC
+    | KeyCode::KeyD
+    | KeyCode::KeyE
+    | KeyCode::KeyF
+    | KeyCode::KeyG
+    | KeyCode::KeyH
+    | KeyCode::KeyI
+    | KeyCode::KeyJ
+    | KeyCode::KeyK
+    | KeyCode::KeyL
+    | KeyCode::KeyM
+    | KeyCode::KeyN
+    | KeyCode::KeyO
+    | KeyCode::KeyP
+    | KeyCode::KeyQ
+    | KeyCode::KeyR
+    | KeyCode::KeyS
+    | KeyCode::KeyT
+    | KeyCode::KeyU
+    | KeyCode::KeyV
+    | KeyCode::KeyW
+    | KeyCode::KeyX
+    | KeyCode::KeyY
+    | KeyCode::KeyZ => name["Key".len()..].to_string(),
+    KeyCode::Digit0
+    | KeyCode::Digit1
+    | KeyCode::Digit2
+    | KeyCode::Digit3
+    | KeyCode::Digit4
+    | KeyCode::Digit5
+    | KeyCode::Digit6
+    | KeyCode::Digit7
+    | KeyCode::Digit8
+    | KeyCode::Digit9 => name["Digit".len()..].to_string(),
+    _ => name,
+  }
+}
+
+/// Returns the symbol macOS shows in menus for `key`, if it has one.
+fn key_symbol(key: KeyCode) -> Option<String> {
+  let symbol = match key {
+    KeyCode::Enter | KeyCode::NumpadEnter => "↩",
+    KeyCode::Backspace => "⌫",
+    KeyCode::Delete => "⌦",
+    KeyCode::Escape => "⎋",
+    KeyCode::Tab => "⇥",
+    KeyCode::Space => "␣",
+    KeyCode::ArrowUp => "↑",
+    KeyCode::ArrowDown => "↓",
+    KeyCode::ArrowLeft => "←",
+    KeyCode::ArrowRight => "→",
+    KeyCode::PageUp => "⇞",
+    KeyCode::PageDown => "⇟",
+    KeyCode::Home => "↖",
+    KeyCode::End => "↘",
+    KeyCode::Backquote => "`",
+    KeyCode::Minus => "-",
+    KeyCode::Equal => "=",
+    KeyCode::BracketLeft => "[",
+    KeyCode::BracketRight => "]",
+    KeyCode::Backslash => "\\",
+    KeyCode::Semicolon => ";",
+    KeyCode::Quote => "'",
+    KeyCode::Comma => ",",
+    KeyCode::Period => ".",
+    KeyCode::Slash => "/",
+    _ => return None,
+  };
+  Some(symbol.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::keyboard::NativeKeyCode;
+
+  #[test]
+  fn parse_accelerator() {
+    let accelerator: Accelerator = "Ctrl+Shift+K".parse().unwrap();
+    assert_eq!(
+      accelerator,
+      Accelerator::new(
+        ModifiersState::CONTROL | ModifiersState::SHIFT,
+        KeyCode::KeyK
+      )
+      .unwrap()
+    );
+    assert_eq!(
+      " alt + numpad0 ".parse::<Accelerator>().unwrap(),
+      Accelerator::new(ModifiersState::ALT, KeyCode::Numpad0).unwrap()
+    );
+
+    assert_eq!("".parse::<Accelerator>(), Err(AcceleratorParseError::Empty));
+    assert_eq!(
+      "Ctrl+".parse::<Accelerator>(),
+      Err(AcceleratorParseError::Empty)
+    );
+    assert_eq!(
+      "Ctrl+Shift".parse::<Accelerator>(),
+      Err(AcceleratorParseError::MissingKey)
+    );
+    assert_eq!(
+      "K+L".parse::<Accelerator>(),
+      Err(AcceleratorParseError::MultipleKeys)
+    );
+    assert_eq!(
+      "Meh+K".parse::<Accelerator>(),
+      Err(AcceleratorParseError::UnknownKey("Meh".into()))
+    );
+  }
+
+  #[test]
+  fn accelerator_round_trip() {
+    for s in [
+      "Control+Shift+K",
+      "Alt+Super+5",
+      "F12",
+      "Control+Numpad0",
+      "Shift+ArrowUp",
+      "Super+Equal",
+      "Alt+NumpadSubtract",
+      "AudioVolumeMute",
+    ] {
+      let accelerator: Accelerator = s.parse().unwrap();
+      assert_eq!(accelerator.to_string(), s);
+    }
+  }
+
+  #[test]
+  fn sided_modifiers_are_combined() {
+    let accelerator = Accelerator::new(
+      ModifiersState::CONTROL | ModifiersState::LCONTROL,
+      KeyCode::KeyK,
+    )
+    .unwrap();
+    assert_eq!(accelerator.mods(), ModifiersState::CONTROL);
+    assert_eq!(
+      combined_modifiers(ModifiersState::RSHIFT | ModifiersState::LALT),
+      ModifiersState::SHIFT | ModifiersState::ALT
+    );
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  #[test]
+  fn matches_key_presses() {
+    use crate::{
+      keyboard::{Key, KeyLocation},
+      platform_impl::KeyEventExtra,
+    };
+
+    let key_event = |state| KeyEvent {
+      physical_key: KeyCode::KeyK,
+      logical_key: Key::Character("k"),
+      text: Some("k"),
+      location: KeyLocation::Standard,
+      state,
+      repeat: false,
+      platform_specific: KeyEventExtra {
+        text_with_all_modifiers: Some("k"),
+        key_without_modifiers: Key::Character("k"),
+      },
+    };
+    let accelerator: Accelerator = "Ctrl+K".parse().unwrap();
+    assert!(accelerator.matches(&key_event(ElementState::Pressed), ModifiersState::LCONTROL));
+    assert!(!accelerator.matches(&key_event(ElementState::Released), ModifiersState::CONTROL));
+    assert!(!accelerator.matches(
+      &key_event(ElementState::Pressed),
+      ModifiersState::CONTROL | ModifiersState::SHIFT
+    ));
+  }
+
+  /// Every [`KeyCode`] an accelerator can have.
+  const KEY_CODES: &[KeyCode] = &[
+    KeyCode::Backquote,
+    KeyCode::Backslash,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Comma,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Equal,
+    KeyCode::IntlBackslash,
+    KeyCode::IntlRo,
+    KeyCode::IntlYen,
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Minus,
+    KeyCode::Plus,
+    KeyCode::Period,
+    KeyCode::Quote,
+    KeyCode::Semicolon,
+    KeyCode::Slash,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Backspace,
+    KeyCode::CapsLock,
+    KeyCode::ContextMenu,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::Enter,
+    KeyCode::SuperLeft,
+    KeyCode::SuperRight,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Convert,
+    KeyCode::KanaMode,
+    KeyCode::Lang1,
+    KeyCode::Lang2,
+    KeyCode::Lang3,
+    KeyCode::Lang4,
+    KeyCode::Lang5,
+    KeyCode::NonConvert,
+    KeyCode::Delete,
+    KeyCode::End,
+    KeyCode::Help,
+    KeyCode::Home,
+    KeyCode::Insert,
+    KeyCode::PageDown,
+    KeyCode::PageUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::ArrowUp,
+    KeyCode::NumLock,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadBackspace,
+    KeyCode::NumpadClear,
+    KeyCode::NumpadClearEntry,
+    KeyCode::NumpadComma,
+    KeyCode::NumpadDecimal,
+    KeyCode::NumpadDivide,
+    KeyCode::NumpadEnter,
+    KeyCode::NumpadEqual,
+    KeyCode::NumpadHash,
+    KeyCode::NumpadMemoryAdd,
+    KeyCode::NumpadMemoryClear,
+    KeyCode::NumpadMemoryRecall,
+    KeyCode::NumpadMemoryStore,
+    KeyCode::NumpadMemorySubtract,
+    KeyCode::NumpadMultiply,
+    KeyCode::NumpadParenLeft,
+    KeyCode::NumpadParenRight,
+    KeyCode::NumpadStar,
+    KeyCode::NumpadSubtract,
+    KeyCode::Escape,
+    KeyCode::Fn,
+    KeyCode::FnLock,
+    KeyCode::PrintScreen,
+    KeyCode::ScrollLock,
+    KeyCode::Pause,
+    KeyCode::BrowserBack,
+    KeyCode::BrowserFavorites,
+    KeyCode::BrowserForward,
+    KeyCode::BrowserHome,
+    KeyCode::BrowserRefresh,
+    KeyCode::BrowserSearch,
+    KeyCode::BrowserStop,
+    KeyCode::Eject,
+    KeyCode::LaunchApp1,
+    KeyCode::LaunchApp2,
+    KeyCode::LaunchMail,
+    KeyCode::MediaPlayPause,
+    KeyCode::MediaSelect,
+    KeyCode::MediaStop,
+    KeyCode::MediaTrackNext,
+    KeyCode::MediaTrackPrevious,
+    KeyCode::Power,
+    KeyCode::Sleep,
+    KeyCode::AudioVolumeDown,
+    KeyCode::AudioVolumeMute,
+    KeyCode::AudioVolumeUp,
+    KeyCode::WakeUp,
+    KeyCode::Hyper,
+    KeyCode::Turbo,
+    KeyCode::Abort,
+    KeyCode::Resume,
+    KeyCode::Suspend,
+    KeyCode::Again,
+    KeyCode::Copy,
+    KeyCode::Cut,
+    KeyCode::Find,
+    KeyCode::Open,
+    KeyCode::Paste,
+    KeyCode::Props,
+    KeyCode::Select,
+    KeyCode::Undo,
+    KeyCode::Hiragana,
+    KeyCode::Katakana,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::F13,
+    KeyCode::F14,
+    KeyCode::F15,
+    KeyCode::F16,
+    KeyCode::F17,
+    KeyCode::F18,
+    KeyCode::F19,
+    KeyCode::F20,
+    KeyCode::F21,
+    KeyCode::F22,
+    KeyCode::F23,
+    KeyCode::F24,
+    KeyCode::F25,
+    KeyCode::F26,
+    KeyCode::F27,
+    KeyCode::F28,
+    KeyCode::F29,
+    KeyCode::F30,
+    KeyCode::F31,
+    KeyCode::F32,
+    KeyCode::F33,
+    KeyCode::F34,
+    KeyCode::F35,
+  ];
+
+  #[test]
+  fn every_key_round_trips() {
+    for &key in KEY_CODES {
+      let accelerator = Accelerator::new(ModifiersState::CONTROL, key).unwrap();
+      assert_eq!(accelerator.to_string().parse(), Ok(accelerator));
+    }
+    assert_eq!(
+      Accelerator::new(
+        ModifiersState::empty(),
+        KeyCode::Unidentified(NativeKeyCode::Unidentified)
+      ),
+      Err(AcceleratorParseError::UnknownKey("Unidentified".into()))
+    );
+  }
+}
@@ -28,7 +28,7 @@ pub struct NotSupportedError {
 pub struct OsError {
   line: u32,
   file: &'static str,
-  error: platform_impl::OsError,
+  pub(crate) error: platform_impl::OsError,
 }
 
 impl NotSupportedError {
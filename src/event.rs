@@ -41,8 +41,10 @@ use std::path::PathBuf;
 use std::time::Instant;
 
 use crate::{
+  accelerator::GlobalHotKeyId,
   dpi::{PhysicalPosition, PhysicalSize},
   keyboard::{self, ModifiersState},
+  monitor::MonitorHandle,
   platform_impl,
   window::{Theme, WindowId},
 };
@@ -75,6 +77,17 @@ pub enum Event<'a, T: 'static> {
     event: DeviceEvent,
   },
 
+  /// Emitted when a monitor is connected, disconnected or its configuration changes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Windows / iOS / Android:** Unsupported.
+  #[non_exhaustive]
+  MonitorEvent {
+    monitor: MonitorHandle,
+    event: MonitorEvent,
+  },
+
   /// Emitted when an event is sent from [`EventLoopProxy::send_event`](crate::event_loop::EventLoopProxy::send_event)
   UserEvent(T),
 
@@ -135,14 +148,38 @@ pub enum Event<'a, T: 'static> {
   LoopDestroyed,
 
   /// Emitted when the app is open by external resources, like opening a file or deeplink.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Emitted once the event loop starts with the URLs and the existing files among
+  ///   the command line arguments, and when files are opened through the
+  ///   `org.freedesktop.Application` D-Bus interface, which requires an application ID.
   Opened { urls: Vec<url::Url> },
 
+  /// Emitted on the primary instance when another instance of the app is started, with the
+  /// command line arguments and working directory of that instance, which then exits.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Only emitted when the single-instance mode is enabled.
+  /// - **Other**: Unsupported.
+  #[non_exhaustive]
+  SecondInstance { args: Vec<String>, cwd: PathBuf },
+
   /// ## Platform-specific
   ///
   /// - **macOS**: https://developer.apple.com/documentation/appkit/nsapplicationdelegate/1428638-applicationshouldhandlereopen with return value same as hasVisibleWindows
   /// - **Other**: Unsupported.
   #[non_exhaustive]
   Reopen { has_visible_windows: bool },
+
+  /// Emitted when a global hotkey is pressed, even if no window of the app is focused.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(X11):** See `EventLoopWindowTargetExtUnix::register_global_hotkey`.
+  /// - **Other**: Unsupported.
+  GlobalHotKey(GlobalHotKeyId),
 }
 
 impl<T: Clone> Clone for Event<'static, T> {
@@ -158,6 +195,10 @@ impl<T: Clone> Clone for Event<'static, T> {
         device_id: *device_id,
         event: event.clone(),
       },
+      MonitorEvent { monitor, event } => MonitorEvent {
+        monitor: monitor.clone(),
+        event: *event,
+      },
       NewEvents(cause) => NewEvents(*cause),
       MainEventsCleared => MainEventsCleared,
       RedrawRequested(wid) => RedrawRequested(*wid),
@@ -166,11 +207,16 @@ impl<T: Clone> Clone for Event<'static, T> {
       Suspended => Suspended,
       Resumed => Resumed,
       Opened { urls } => Opened { urls: urls.clone() },
+      SecondInstance { args, cwd } => SecondInstance {
+        args: args.clone(),
+        cwd: cwd.clone(),
+      },
       Reopen {
         has_visible_windows,
       } => Reopen {
         has_visible_windows: *has_visible_windows,
       },
+      GlobalHotKey(id) => GlobalHotKey(*id),
     }
   }
 }
@@ -182,6 +228,7 @@ impl<'a, T> Event<'a, T> {
       UserEvent(_) => Err(self),
       WindowEvent { window_id, event } => Ok(WindowEvent { window_id, event }),
       DeviceEvent { device_id, event } => Ok(DeviceEvent { device_id, event }),
+      MonitorEvent { monitor, event } => Ok(MonitorEvent { monitor, event }),
       NewEvents(cause) => Ok(NewEvents(cause)),
       MainEventsCleared => Ok(MainEventsCleared),
       RedrawRequested(wid) => Ok(RedrawRequested(wid)),
@@ -190,11 +237,13 @@ impl<'a, T> Event<'a, T> {
       Suspended => Ok(Suspended),
       Resumed => Ok(Resumed),
       Opened { urls } => Ok(Opened { urls }),
+      SecondInstance { args, cwd } => Ok(SecondInstance { args, cwd }),
       Reopen {
         has_visible_windows,
       } => Ok(Reopen {
         has_visible_windows,
       }),
+      GlobalHotKey(id) => Ok(GlobalHotKey(id)),
     }
   }
 
@@ -208,6 +257,7 @@ impl<'a, T> Event<'a, T> {
         .map(|event| WindowEvent { window_id, event }),
       UserEvent(event) => Some(UserEvent(event)),
       DeviceEvent { device_id, event } => Some(DeviceEvent { device_id, event }),
+      MonitorEvent { monitor, event } => Some(MonitorEvent { monitor, event }),
       NewEvents(cause) => Some(NewEvents(cause)),
       MainEventsCleared => Some(MainEventsCleared),
       RedrawRequested(wid) => Some(RedrawRequested(wid)),
@@ -216,11 +266,13 @@ impl<'a, T> Event<'a, T> {
       Suspended => Some(Suspended),
       Resumed => Some(Resumed),
       Opened { urls } => Some(Opened { urls }),
+      SecondInstance { args, cwd } => Some(SecondInstance { args, cwd }),
       Reopen {
         has_visible_windows,
       } => Some(Reopen {
         has_visible_windows,
       }),
+      GlobalHotKey(id) => Some(GlobalHotKey(id)),
     }
   }
 }
@@ -300,6 +352,17 @@ pub enum WindowEvent<'a> {
   /// The window received a unicode character.
   ReceivedImeText(String),
 
+  /// The input method is composing text that isn't committed yet.
+  ///
+  /// Contains the composing (preedit) string and the cursor range as byte offsets into it, `None`
+  /// meaning the cursor should be hidden. An empty string is sent when the composition is cleared,
+  /// the committed text is then delivered with [`WindowEvent::ReceivedImeText`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  ImePreedit(String, Option<(usize, usize)>),
+
   /// The window gained or lost focus.
   ///
   /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -413,7 +476,7 @@ pub enum WindowEvent<'a> {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / Android / iOS:** Unsupported
+  /// - **Android / iOS:** Unsupported
   ThemeChanged(Theme),
 
   /// The window decorations has been clicked.
@@ -436,6 +499,7 @@ impl Clone for WindowEvent<'static> {
       HoveredFile(file) => HoveredFile(file.clone()),
       HoveredFileCancelled => HoveredFileCancelled,
       ReceivedImeText(c) => ReceivedImeText(c.clone()),
+      ImePreedit(text, cursor_range) => ImePreedit(text.clone(), *cursor_range),
       Focused(f) => Focused(*f),
       KeyboardInput {
         device_id,
@@ -528,6 +592,7 @@ impl<'a> WindowEvent<'a> {
       HoveredFile(file) => Some(HoveredFile(file)),
       HoveredFileCancelled => Some(HoveredFileCancelled),
       ReceivedImeText(c) => Some(ReceivedImeText(c)),
+      ImePreedit(text, cursor_range) => Some(ImePreedit(text, cursor_range)),
       Focused(focused) => Some(Focused(focused)),
       KeyboardInput {
         device_id,
@@ -675,6 +740,19 @@ pub enum DeviceEvent {
   },
 }
 
+/// Describes a change of the monitors connected to the system.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MonitorEvent {
+  /// The monitor has been connected.
+  Added,
+  /// The monitor has been disconnected.
+  Removed,
+  /// The position, size, scale factor or work area of the monitor has changed.
+  Changed,
+}
+
 /// Describes a keyboard input as a raw device event.
 ///
 /// Note that holding down a key may produce repeated `RawKeyEvent`s. The
@@ -270,11 +270,20 @@ impl<T> EventLoopWindowTarget<T> {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / macOS / iOS / Android:** Unsupported.
+  /// - **Linux(X11):** Device events are reported regardless of focus by default, as with
+  ///   [`DeviceEventFilter::Never`].
+  /// - **Linux(Wayland) / macOS / iOS / Android:** Unsupported.
   ///
   /// [`DeviceEvent`]: crate::event::DeviceEvent
   pub fn set_device_event_filter(&self, _filter: DeviceEventFilter) {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(
+      target_os = "windows",
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
     self.p.set_device_event_filter(_filter);
   }
 
@@ -282,7 +291,9 @@ impl<T> EventLoopWindowTarget<T> {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android / Linux(Wayland)**: Unsupported, returns `0,0`.
+  /// - **iOS / Android**: Unsupported, returns `0,0`.
+  /// - **Linux(Wayland)**: Returns the last known position relative to the window containing the
+  ///   cursor, or [`ExternalError::NotSupported`] if the cursor is outside all windows.
   #[inline]
   pub fn cursor_position(&self) -> Result<PhysicalPosition<f64>, ExternalError> {
     self.p.cursor_position()
@@ -293,7 +304,7 @@ impl<T> EventLoopWindowTarget<T> {
   /// ## Platform-specific
   ///
   /// - **Windows:** Unsupported. Use the Progress Bar Function Available in Window (Windows can have different progress bars for different window)
-  /// - **Linux:** Only supported desktop environments with `libunity` (e.g. GNOME).
+  /// - **Linux:** Only supported by desktop environments implementing the `com.canonical.Unity.LauncherEntry` D-Bus API (e.g. KDE Plasma, Unity or GNOME with Dash to Dock).
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_progress_bar(&self, _progress: ProgressBarState) {
@@ -353,7 +364,7 @@ impl<T> rwh_06::HasDisplayHandle for EventLoopWindowTarget<T> {
 
 /// Used to send custom events to `EventLoop`.
 pub struct EventLoopProxy<T: 'static> {
-  event_loop_proxy: platform_impl::EventLoopProxy<T>,
+  pub(crate) event_loop_proxy: platform_impl::EventLoopProxy<T>,
 }
 
 impl<T: 'static> Clone for EventLoopProxy<T> {
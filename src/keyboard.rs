@@ -86,19 +86,19 @@ use crate::{
 impl ModifiersState {
   /// Returns `true` if the shift key is pressed.
   pub fn shift_key(&self) -> bool {
-    self.intersects(Self::SHIFT)
+    self.intersects(Self::SHIFT | Self::LSHIFT | Self::RSHIFT)
   }
   /// Returns `true` if the control key is pressed.
   pub fn control_key(&self) -> bool {
-    self.intersects(Self::CONTROL)
+    self.intersects(Self::CONTROL | Self::LCONTROL | Self::RCONTROL)
   }
   /// Returns `true` if the alt key is pressed.
   pub fn alt_key(&self) -> bool {
-    self.intersects(Self::ALT)
+    self.intersects(Self::ALT | Self::LALT | Self::RALT)
   }
   /// Returns `true` if the super key is pressed.
   pub fn super_key(&self) -> bool {
-    self.intersects(Self::SUPER)
+    self.intersects(Self::SUPER | Self::LSUPER | Self::RSUPER)
   }
 }
 
@@ -106,26 +106,33 @@ bitflags! {
     /// Represents the current state of the keyboard modifiers
     ///
     /// Each flag represents a modifier and is set if this modifier is active.
-    #[derive(Clone, Copy, Default, Debug, PartialEq)]
+    #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
     pub struct ModifiersState: u32 {
-        // left and right modifiers are currently commented out, but we should be able to support
-        // them in a future release
+        // The combined flags are set whenever either side is pressed.
         /// The "shift" key.
         const SHIFT = 0b100 << 0;
-        // const LSHIFT = 0b010 << 0;
-        // const RSHIFT = 0b001 << 0;
+        /// The left "shift" key.
+        const LSHIFT = 0b010 << 0;
+        /// The right "shift" key.
+        const RSHIFT = 0b001 << 0;
         /// The "control" key.
         const CONTROL = 0b100 << 3;
-        // const LCTRL = 0b010 << 3;
-        // const RCTRL = 0b001 << 3;
+        /// The left "control" key.
+        const LCONTROL = 0b010 << 3;
+        /// The right "control" key.
+        const RCONTROL = 0b001 << 3;
         /// The "alt" key.
         const ALT = 0b100 << 6;
-        // const LALT = 0b010 << 6;
-        // const RALT = 0b001 << 6;
+        /// The left "alt" key.
+        const LALT = 0b010 << 6;
+        /// The right "alt" key.
+        const RALT = 0b001 << 6;
         /// This is the "windows" key on PC and "command" key on Mac.
         const SUPER = 0b100 << 9;
-        // const LSUPER  = 0b010 << 9;
-        // const RSUPER  = 0b001 << 9;
+        /// The left "super" key.
+        const LSUPER = 0b010 << 9;
+        /// The right "super" key.
+        const RSUPER = 0b001 << 9;
     }
 }
 
@@ -142,6 +149,14 @@ mod modifiers_serde {
     pub control_key: bool,
     pub alt_key: bool,
     pub super_key: bool,
+    pub left_shift_key: bool,
+    pub right_shift_key: bool,
+    pub left_control_key: bool,
+    pub right_control_key: bool,
+    pub left_alt_key: bool,
+    pub right_alt_key: bool,
+    pub left_super_key: bool,
+    pub right_super_key: bool,
   }
 
   impl Serialize for ModifiersState {
@@ -154,6 +169,14 @@ mod modifiers_serde {
         control_key: self.control_key(),
         alt_key: self.alt_key(),
         super_key: self.super_key(),
+        left_shift_key: self.contains(ModifiersState::LSHIFT),
+        right_shift_key: self.contains(ModifiersState::RSHIFT),
+        left_control_key: self.contains(ModifiersState::LCONTROL),
+        right_control_key: self.contains(ModifiersState::RCONTROL),
+        left_alt_key: self.contains(ModifiersState::LALT),
+        right_alt_key: self.contains(ModifiersState::RALT),
+        left_super_key: self.contains(ModifiersState::LSUPER),
+        right_super_key: self.contains(ModifiersState::RSUPER),
       };
       s.serialize(serializer)
     }
@@ -169,12 +192,41 @@ mod modifiers_serde {
         control_key,
         alt_key,
         super_key,
+        left_shift_key,
+        right_shift_key,
+        left_control_key,
+        right_control_key,
+        left_alt_key,
+        right_alt_key,
+        left_super_key,
+        right_super_key,
       } = ModifiersStateSerialize::deserialize(deserializer)?;
       let mut m = ModifiersState::empty();
-      m.set(ModifiersState::SHIFT, shift_key);
-      m.set(ModifiersState::CONTROL, control_key);
-      m.set(ModifiersState::ALT, alt_key);
-      m.set(ModifiersState::SUPER, super_key);
+      m.set(ModifiersState::LSHIFT, left_shift_key);
+      m.set(ModifiersState::RSHIFT, right_shift_key);
+      m.set(ModifiersState::LCONTROL, left_control_key);
+      m.set(ModifiersState::RCONTROL, right_control_key);
+      m.set(ModifiersState::LALT, left_alt_key);
+      m.set(ModifiersState::RALT, right_alt_key);
+      m.set(ModifiersState::LSUPER, left_super_key);
+      m.set(ModifiersState::RSUPER, right_super_key);
+      // The combined flags are implied by either side.
+      m.set(
+        ModifiersState::SHIFT,
+        shift_key || left_shift_key || right_shift_key,
+      );
+      m.set(
+        ModifiersState::CONTROL,
+        control_key || left_control_key || right_control_key,
+      );
+      m.set(
+        ModifiersState::ALT,
+        alt_key || left_alt_key || right_alt_key,
+      );
+      m.set(
+        ModifiersState::SUPER,
+        super_key || left_super_key || right_super_key,
+      );
       Ok(m)
     }
   }
@@ -698,12 +750,14 @@ impl FromStr for KeyCode {
       "NUM7" | "NUMPAD7" => KeyCode::Numpad7,
       "NUM8" | "NUMPAD8" => KeyCode::Numpad8,
       "NUM9" | "NUMPAD9" => KeyCode::Numpad9,
-      "=" => KeyCode::Equal,
-      "-" => KeyCode::Minus,
+      "=" | "EQUAL" => KeyCode::Equal,
+      "-" | "MINUS" => KeyCode::Minus,
       "PLUS" => KeyCode::Plus,
       "." | "PERIOD" => KeyCode::Period,
       "'" | "QUOTE" => KeyCode::Quote,
-      "\\" => KeyCode::IntlBackslash,
+      "\\" | "INTLBACKSLASH" => KeyCode::IntlBackslash,
+      "INTLRO" => KeyCode::IntlRo,
+      "INTLYEN" => KeyCode::IntlYen,
       "A" => KeyCode::KeyA,
       "B" => KeyCode::KeyB,
       "C" => KeyCode::KeyC,
@@ -740,11 +794,27 @@ impl FromStr for KeyCode {
       "SPACE" => KeyCode::Space,
       "TAB" => KeyCode::Tab,
       "CONVERT" => KeyCode::Convert,
+      "ALTLEFT" => KeyCode::AltLeft,
+      "ALTRIGHT" => KeyCode::AltRight,
+      "CONTROLLEFT" => KeyCode::ControlLeft,
+      "CONTROLRIGHT" => KeyCode::ControlRight,
+      "SUPERLEFT" => KeyCode::SuperLeft,
+      "SUPERRIGHT" => KeyCode::SuperRight,
+      "SHIFTLEFT" => KeyCode::ShiftLeft,
+      "SHIFTRIGHT" => KeyCode::ShiftRight,
+      "KANAMODE" => KeyCode::KanaMode,
+      "LANG1" => KeyCode::Lang1,
+      "LANG2" => KeyCode::Lang2,
+      "LANG3" => KeyCode::Lang3,
+      "LANG4" => KeyCode::Lang4,
+      "LANG5" => KeyCode::Lang5,
+      "NONCONVERT" => KeyCode::NonConvert,
 
       "DELETE" => KeyCode::Delete,
       "END" => KeyCode::End,
       "HELP" => KeyCode::Help,
       "HOME" => KeyCode::Home,
+      "INSERT" => KeyCode::Insert,
       "PAGEDOWN" => KeyCode::PageDown,
       "PAGEUP" => KeyCode::PageUp,
 
@@ -759,8 +829,23 @@ impl FromStr for KeyCode {
       "NUMCLEAR" | "NUMPADCLEAR" => KeyCode::NumpadClear,
       "NUMCOMMA" | "NUMPADCOMMA" => KeyCode::NumpadComma,
       "NUMDIVIDE" | "NUMPADDIVIDE" => KeyCode::NumpadDivide,
-      "NUMSUBSTRACT" | "NUMPADSUBSTRACT" => KeyCode::NumpadSubtract,
+      "NUMSUBSTRACT" | "NUMPADSUBSTRACT" | "NUMSUBTRACT" | "NUMPADSUBTRACT" => {
+        KeyCode::NumpadSubtract
+      }
       "NUMENTER" | "NUMPADENTER" => KeyCode::NumpadEnter,
+      "NUMPADCLEARENTRY" => KeyCode::NumpadClearEntry,
+      "NUMPADDECIMAL" => KeyCode::NumpadDecimal,
+      "NUMPADEQUAL" => KeyCode::NumpadEqual,
+      "NUMPADHASH" => KeyCode::NumpadHash,
+      "NUMPADMEMORYADD" => KeyCode::NumpadMemoryAdd,
+      "NUMPADMEMORYCLEAR" => KeyCode::NumpadMemoryClear,
+      "NUMPADMEMORYRECALL" => KeyCode::NumpadMemoryRecall,
+      "NUMPADMEMORYSTORE" => KeyCode::NumpadMemoryStore,
+      "NUMPADMEMORYSUBTRACT" => KeyCode::NumpadMemorySubtract,
+      "NUMPADMULTIPLY" => KeyCode::NumpadMultiply,
+      "NUMPADPARENLEFT" => KeyCode::NumpadParenLeft,
+      "NUMPADPARENRIGHT" => KeyCode::NumpadParenRight,
+      "NUMPADSTAR" => KeyCode::NumpadStar,
 
       "ESC" | "ESCAPE" => KeyCode::Escape,
       "FN" => KeyCode::Fn,
@@ -770,15 +855,45 @@ impl FromStr for KeyCode {
 
       "PAUSE" => KeyCode::Pause,
 
-      "VOLUMEMUTE" => KeyCode::AudioVolumeMute,
-      "VOLUMEDOWN" => KeyCode::AudioVolumeDown,
-      "VOLUMEUP" => KeyCode::AudioVolumeUp,
-      "MEDIANEXTTRACK" => KeyCode::MediaTrackNext,
-      "MEDIAPREVIOUSTRACK" => KeyCode::MediaTrackPrevious,
+      "VOLUMEMUTE" | "AUDIOVOLUMEMUTE" => KeyCode::AudioVolumeMute,
+      "VOLUMEDOWN" | "AUDIOVOLUMEDOWN" => KeyCode::AudioVolumeDown,
+      "VOLUMEUP" | "AUDIOVOLUMEUP" => KeyCode::AudioVolumeUp,
+      "MEDIANEXTTRACK" | "MEDIATRACKNEXT" => KeyCode::MediaTrackNext,
+      "MEDIAPREVIOUSTRACK" | "MEDIATRACKPREVIOUS" => KeyCode::MediaTrackPrevious,
       "MEDIAPLAYPAUSE" => KeyCode::MediaPlayPause,
       "LAUNCHMAIL" => KeyCode::LaunchMail,
+      "BROWSERBACK" => KeyCode::BrowserBack,
+      "BROWSERFAVORITES" => KeyCode::BrowserFavorites,
+      "BROWSERFORWARD" => KeyCode::BrowserForward,
+      "BROWSERHOME" => KeyCode::BrowserHome,
+      "BROWSERREFRESH" => KeyCode::BrowserRefresh,
+      "BROWSERSEARCH" => KeyCode::BrowserSearch,
+      "BROWSERSTOP" => KeyCode::BrowserStop,
+      "EJECT" => KeyCode::Eject,
+      "LAUNCHAPP1" => KeyCode::LaunchApp1,
+      "LAUNCHAPP2" => KeyCode::LaunchApp2,
+      "MEDIASELECT" => KeyCode::MediaSelect,
+      "MEDIASTOP" => KeyCode::MediaStop,
+      "POWER" => KeyCode::Power,
+      "SLEEP" => KeyCode::Sleep,
+      "WAKEUP" => KeyCode::WakeUp,
 
       "SUSPEND" => KeyCode::Suspend,
+      "HYPER" => KeyCode::Hyper,
+      "TURBO" => KeyCode::Turbo,
+      "ABORT" => KeyCode::Abort,
+      "RESUME" => KeyCode::Resume,
+      "AGAIN" => KeyCode::Again,
+      "COPY" => KeyCode::Copy,
+      "CUT" => KeyCode::Cut,
+      "FIND" => KeyCode::Find,
+      "OPEN" => KeyCode::Open,
+      "PASTE" => KeyCode::Paste,
+      "PROPS" => KeyCode::Props,
+      "SELECT" => KeyCode::Select,
+      "UNDO" => KeyCode::Undo,
+      "HIRAGANA" => KeyCode::Hiragana,
+      "KATAKANA" => KeyCode::Katakana,
       "F1" => KeyCode::F1,
       "F2" => KeyCode::F2,
       "F3" => KeyCode::F3,
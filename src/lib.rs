@@ -172,6 +172,7 @@ extern crate objc;
 
 pub use dpi;
 
+pub mod accelerator;
 #[macro_use]
 pub mod error;
 pub mod event;
@@ -152,10 +152,61 @@ impl MonitorHandle {
     self.inner.scale_factor()
   }
 
+  /// Returns the current refresh rate of the monitor in millihertz.
+  ///
+  /// Returns `None` if the refresh rate can't be determined.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS:** Returns the maximum frame rate of the screen.
+  /// - **Android:** Unsupported, always returns `None`.
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    self.inner.refresh_rate_millihertz()
+  }
+
+  /// Returns the physical size of the monitor in millimetres, as `(width, height)`.
+  ///
+  /// Returns `None` if the size is unknown, as for projectors.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported, always returns `None`.
+  #[inline]
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    self.inner.physical_size_mm()
+  }
+
+  /// Returns the position and size of the area of the monitor which is not covered by panels,
+  /// docks and taskbars.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(Wayland) / iOS / Android:** Returns the whole monitor area.
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    self.inner.work_area()
+  }
+
+  /// Returns an identifier of the monitor which stays the same when it is reconnected,
+  /// unlike the order of [`EventLoopWindowTarget::available_monitors`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Made of the manufacturer and model of the monitor, or its connector name on X11,
+  ///   so identical monitors may share it.
+  /// - **iOS / Android:** Unsupported, always returns `None`.
+  ///
+  /// [`EventLoopWindowTarget::available_monitors`]: crate::event_loop::EventLoopWindowTarget::available_monitors
+  #[inline]
+  pub fn stable_id(&self) -> Option<String> {
+    self.inner.stable_id()
+  }
+
   /// Returns all fullscreen video modes supported by this monitor.
   ///
   /// ## Platform-specific
-  /// - **Linux:** Unsupported. This will always return empty iterator.
+  /// - **Linux(Wayland):** Unsupported. This will always return empty iterator.
   #[inline]
   pub fn video_modes(&self) -> impl Iterator<Item = VideoMode> {
     self.inner.video_modes()
@@ -10,7 +10,13 @@
   target_os = "openbsd"
 ))]
 
-use std::{os::raw::c_int, sync::Arc};
+use std::{
+  marker::PhantomData,
+  os::raw::{c_ulong, c_void},
+  sync::Arc,
+};
+
+use gtk::gdk::prelude::*;
 
 // XConnection utilities
 #[doc(hidden)]
@@ -18,14 +24,22 @@ pub use crate::platform_impl::x11;
 
 pub use crate::platform_impl::EventLoop as UnixEventLoop;
 use crate::{
+  accelerator::{Accelerator, GlobalHotKeyId},
   error::{ExternalError, OsError},
-  event_loop::{EventLoopBuilder, EventLoopWindowTarget},
+  event::{DeviceEvent, DeviceId, Event, WindowEvent},
+  event_loop::{
+    EventLoop, EventLoopBuilder, EventLoopClosed, EventLoopProxy, EventLoopWindowTarget,
+  },
   monitor::MonitorHandle,
-  platform_impl::{x11::xdisplay::XError, Parent, Window as UnixWindow},
-  window::{Window, WindowBuilder},
+  platform_impl::{Parent, Window as UnixWindow},
+  window::{UserAttentionType, Window, WindowBuilder, WindowId},
 };
 
-use self::x11::xdisplay::XConnection;
+pub use self::x11::{
+  register_x_error_hook,
+  xdisplay::{XConnection, XError},
+  XErrorHook,
+};
 
 /// Additional methods on `EventLoop` that are specific to Unix.
 pub trait EventLoopBuilderExtUnix {
@@ -45,6 +59,19 @@ pub trait EventLoopBuilderExtUnix {
   ///
   /// If no application ID is given then some features (most notably application uniqueness) will be disabled.
   fn with_app_id<S: Into<String>>(&mut self, id: S) -> &mut Self;
+
+  /// Whether to allow a single instance of the application to run.
+  ///
+  /// When another instance is started, it forwards its command line arguments and working
+  /// directory to the primary instance as [`Event::SecondInstance`].
+  /// Requires an application ID, see [`EventLoopBuilderExtUnix::with_app_id`].
+  ///
+  /// [`EventLoopBuilder::build`] then exits the process of the other instance with status 0.
+  /// [`EventLoopBuilderTryBuildExtUnix::try_build`] returns an [`OsErrorKind::SecondInstance`]
+  /// error instead, so it can clean up and exit itself.
+  ///
+  /// [`Event::SecondInstance`]: crate::event::Event::SecondInstance
+  fn with_single_instance(&mut self, single_instance: bool) -> &mut Self;
 }
 
 impl<T> EventLoopBuilderExtUnix for EventLoopBuilder<T> {
@@ -58,6 +85,35 @@ impl<T> EventLoopBuilderExtUnix for EventLoopBuilder<T> {
     self.platform_specific.app_id = Some(id.into());
     self
   }
+
+  #[inline]
+  fn with_single_instance(&mut self, single_instance: bool) -> &mut Self {
+    self.platform_specific.single_instance = single_instance;
+    self
+  }
+}
+
+/// Fallible creation of an `EventLoop`, specific to Unix.
+pub trait EventLoopBuilderTryBuildExtUnix<T> {
+  /// Builds a new event loop, returning an error instead of panicking if it can't be created.
+  ///
+  /// Fails with [`OsErrorKind::NoDisplay`] when no display server is configured, and with
+  /// [`OsErrorKind::Other`] when called off the main thread without
+  /// [`EventLoopBuilderExtUnix::with_any_thread`]. Later instances of single-instance apps fail
+  /// with [`OsErrorKind::SecondInstance`].
+  fn try_build(&mut self) -> Result<EventLoop<T>, OsError>;
+}
+
+impl<T> EventLoopBuilderTryBuildExtUnix<T> for EventLoopBuilder<T> {
+  fn try_build(&mut self) -> Result<EventLoop<T>, OsError> {
+    match UnixEventLoop::try_new(&self.platform_specific) {
+      Ok(event_loop) => Ok(EventLoop {
+        event_loop,
+        _marker: PhantomData,
+      }),
+      Err(e) => Err(os_error!(e)),
+    }
+  }
 }
 
 /// Additional methods on `Window` that are specific to Unix.
@@ -77,10 +133,50 @@ pub trait WindowExtUnix {
   /// Returns `None` if the default vertical `gtk::Box` creation was disabled by [`WindowBuilderExtUnix::with_default_vbox`].
   fn default_vbox(&self) -> Option<&gtk::Box>;
 
+  /// Returns a pointer to the `wl_surface` object of wayland that is used by this window.
+  ///
+  /// Returns `None` if the window doesn't use wayland or isn't realized yet.
+  ///
+  /// The pointer will become invalid when the window is destroyed.
+  fn wayland_surface(&self) -> Option<*mut c_void>;
+
+  /// Returns the ID of the X11 window used by this window.
+  ///
+  /// Returns `None` if the window doesn't use X11 or isn't realized yet.
+  fn xlib_window(&self) -> Option<c_ulong>;
+
   /// Whether to show the window icon in the taskbar or not.
   fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError>;
 
   fn set_badge_count(&self, count: Option<i64>, desktop_filename: Option<String>);
+
+  /// Sets the type of the window, used by the window manager to decide how to present it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn set_window_type(&self, window_type: WindowType);
+
+  /// Whether to hide the window from pagers and workspace switchers.
+  ///
+  /// Note that [`WindowExtUnix::set_skip_taskbar`] also sets this hint.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn set_skip_pager(&self, skip: bool);
+
+  /// Sets or clears the urgency hint of the window, like
+  /// [`Window::request_user_attention`].
+  fn set_urgent(&self, urgent: bool);
+
+  /// Whether the window bypasses the window manager, which neither decorates nor moves it.
+  /// A visible window is unmapped and mapped again to apply the change.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn set_override_redirect(&self, override_redirect: bool);
 }
 
 impl WindowExtUnix for Window {
@@ -92,6 +188,14 @@ impl WindowExtUnix for Window {
     self.window.default_vbox.as_ref()
   }
 
+  fn wayland_surface(&self) -> Option<*mut c_void> {
+    self.window.wayland_surface()
+  }
+
+  fn xlib_window(&self) -> Option<c_ulong> {
+    self.window.xlib_window()
+  }
+
   fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError> {
     self.window.set_skip_taskbar(skip)
   }
@@ -107,6 +211,24 @@ impl WindowExtUnix for Window {
   fn set_badge_count(&self, count: Option<i64>, desktop_filename: Option<String>) {
     self.window.set_badge_count(count, desktop_filename);
   }
+
+  fn set_window_type(&self, window_type: WindowType) {
+    self.window.set_window_type(window_type);
+  }
+
+  fn set_skip_pager(&self, skip: bool) {
+    self.window.set_skip_pager(skip);
+  }
+
+  fn set_urgent(&self, urgent: bool) {
+    self
+      .window
+      .request_user_attention(urgent.then_some(UserAttentionType::Critical));
+  }
+
+  fn set_override_redirect(&self, override_redirect: bool) {
+    self.window.set_override_redirect(override_redirect);
+  }
 }
 
 pub trait WindowBuilderExtUnix {
@@ -150,6 +272,68 @@ pub trait WindowBuilderExtUnix {
   /// Whether to create a vertical `gtk::Box` and add it as the sole child of this window.
   /// Created by default.
   fn with_default_vbox(self, add: bool) -> WindowBuilder;
+
+  /// Sets the type of the window, used by the window manager to decide how to present it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn with_window_type(self, window_type: WindowType) -> WindowBuilder;
+
+  /// Whether to hide the window from pagers and workspace switchers.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn with_skip_pager(self, skip: bool) -> WindowBuilder;
+
+  /// Whether to create the window with the urgency hint set.
+  fn with_urgent(self, urgent: bool) -> WindowBuilder;
+
+  /// Whether the window bypasses the window manager, which neither decorates nor moves it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported.
+  fn with_override_redirect(self, override_redirect: bool) -> WindowBuilder;
+
+  /// Creates the window as a layer-shell surface on `layer`, for panels, docks and overlays.
+  ///
+  /// The other layer-shell options only apply to windows created with this option.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Requires `libgtk-layer-shell.so.0` and a compositor supporting the
+  ///   `wlr-layer-shell` protocol, otherwise a normal window is created.
+  /// - **X11:** The window is a `_NET_WM_WINDOW_TYPE_DOCK` window placed according to the
+  ///   anchor and margins, kept above other windows on [`Layer::Top`] and [`Layer::Overlay`]
+  ///   and below them otherwise. The exclusive zone is reserved with `_NET_WM_STRUT_PARTIAL`.
+  fn with_layer_shell(self, layer: Layer) -> WindowBuilder;
+
+  /// Sets the screen edges the layer-shell window is anchored to. Anchoring to two opposite
+  /// edges stretches the window between them.
+  ///
+  /// Default is [`Anchor::empty`], which centers the window.
+  fn with_layer_anchor(self, anchor: Anchor) -> WindowBuilder;
+
+  /// Sets the margins of the layer-shell window from the edges it's anchored to, in logical
+  /// pixels.
+  ///
+  /// Default is `0` for all edges.
+  fn with_layer_margins(self, top: i32, right: i32, bottom: i32, left: i32) -> WindowBuilder;
+
+  /// Sets the size in logical pixels of the area along the anchored edge that other windows
+  /// shouldn't cover. It only applies when anchored to a single edge, or to an edge and both
+  /// perpendicular ones.
+  ///
+  /// Default is `0`, which doesn't reserve any space. `-1` asks the window to not be moved by
+  /// the exclusive zones of other windows.
+  fn with_exclusive_zone(self, exclusive_zone: i32) -> WindowBuilder;
+
+  /// Sets how the layer-shell window receives keyboard focus.
+  ///
+  /// Default is [`KeyboardInteractivity::None`].
+  fn with_keyboard_interactivity(self, interactivity: KeyboardInteractivity) -> WindowBuilder;
 }
 
 impl WindowBuilderExtUnix for WindowBuilder {
@@ -193,6 +377,154 @@ impl WindowBuilderExtUnix for WindowBuilder {
     self.platform_specific.default_vbox = add;
     self
   }
+
+  fn with_window_type(mut self, window_type: WindowType) -> WindowBuilder {
+    self.platform_specific.window_type = Some(window_type);
+    self
+  }
+
+  fn with_skip_pager(mut self, skip: bool) -> WindowBuilder {
+    self.platform_specific.skip_pager = skip;
+    self
+  }
+
+  fn with_urgent(mut self, urgent: bool) -> WindowBuilder {
+    self.platform_specific.urgent = urgent;
+    self
+  }
+
+  fn with_override_redirect(mut self, override_redirect: bool) -> WindowBuilder {
+    self.platform_specific.override_redirect = override_redirect;
+    self
+  }
+
+  fn with_layer_shell(mut self, layer: Layer) -> WindowBuilder {
+    self.platform_specific.layer = Some(layer);
+    self
+  }
+
+  fn with_layer_anchor(mut self, anchor: Anchor) -> WindowBuilder {
+    self.platform_specific.layer_shell.anchor = anchor;
+    self
+  }
+
+  fn with_layer_margins(mut self, top: i32, right: i32, bottom: i32, left: i32) -> WindowBuilder {
+    self.platform_specific.layer_shell.margins = [top, right, bottom, left];
+    self
+  }
+
+  fn with_exclusive_zone(mut self, exclusive_zone: i32) -> WindowBuilder {
+    self.platform_specific.layer_shell.exclusive_zone = exclusive_zone;
+    self
+  }
+
+  fn with_keyboard_interactivity(mut self, interactivity: KeyboardInteractivity) -> WindowBuilder {
+    self.platform_specific.layer_shell.keyboard_interactivity = interactivity;
+    self
+  }
+}
+
+/// The type of a window, set as `_NET_WM_WINDOW_TYPE` on X11. See
+/// [`WindowBuilderExtUnix::with_window_type`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WindowType {
+  /// A normal top-level window.
+  #[default]
+  Normal,
+  /// A dialog window.
+  Dialog,
+  /// A persistent utility window, like a palette or toolbox.
+  Utility,
+  /// A splash screen displayed while the application is starting.
+  Splash,
+  /// A notification popup.
+  Notification,
+  /// A tooltip.
+  Tooltip,
+  /// A popup menu, like a context menu.
+  PopupMenu,
+}
+
+/// The layer of a layer-shell window, see [`WindowBuilderExtUnix::with_layer_shell`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Layer {
+  /// Below all the other layers, e.g. for wallpapers.
+  Background,
+  /// Below normal windows.
+  Bottom,
+  /// Above normal windows, e.g. for panels.
+  #[default]
+  Top,
+  /// Above all the other layers, including fullscreen windows.
+  Overlay,
+}
+
+bitflags! {
+  /// The screen edges a layer-shell window is anchored to, see
+  /// [`WindowBuilderExtUnix::with_layer_anchor`].
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+  pub struct Anchor: u8 {
+    const TOP = 1 << 0;
+    const RIGHT = 1 << 1;
+    const BOTTOM = 1 << 2;
+    const LEFT = 1 << 3;
+  }
+}
+
+/// How a layer-shell window receives keyboard focus, see
+/// [`WindowBuilderExtUnix::with_keyboard_interactivity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyboardInteractivity {
+  /// The window never receives keyboard focus.
+  #[default]
+  None,
+  /// The window takes the keyboard focus while it's shown, on the top and overlay layers.
+  Exclusive,
+  /// The window receives keyboard focus like normal windows.
+  OnDemand,
+}
+
+/// The cause of an [`OsError`], see [`OsErrorExtUnix::kind`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OsErrorKind {
+  /// gtk or the gtk application could not be initialized.
+  GtkInit,
+  /// No display server is available.
+  NoDisplay,
+  /// The window isn't realized yet, which happens once it's shown.
+  NotRealized,
+  /// Wayland, or the Wayland compositor, doesn't support the operation.
+  WaylandUnsupported,
+  /// The X server failed the request with this error code.
+  X(u8),
+  /// The command line was forwarded to the primary instance of the app, see
+  /// [`EventLoopBuilderExtUnix::with_single_instance`].
+  SecondInstance,
+  /// Any other failure, see [`OsErrorExtUnix::message`].
+  Other,
+}
+
+/// Additional methods on `OsError` that are specific to Unix.
+pub trait OsErrorExtUnix {
+  /// Returns the cause of the error.
+  fn kind(&self) -> OsErrorKind;
+
+  /// Returns a description of the error, without the location it was raised at.
+  fn message(&self) -> &str;
+}
+
+impl OsErrorExtUnix for OsError {
+  #[inline]
+  fn kind(&self) -> OsErrorKind {
+    self.error.kind
+  }
+
+  #[inline]
+  fn message(&self) -> &str {
+    &self.error.message
+  }
 }
 
 /// Additional methods on `EventLoopWindowTarget` that are specific to Unix.
@@ -203,21 +535,49 @@ pub trait EventLoopWindowTargetExtUnix {
   /// True if the `EventLoopWindowTarget` uses X11.
   fn is_x11(&self) -> bool;
 
+  /// Returns the X connection shared by the event loop, opening it on first use.
+  ///
+  /// Returns `None` if the `EventLoopWindowTarget` doesn't use X11 or the connection can't be
+  /// opened.
   fn xlib_xconnection(&self) -> Option<Arc<XConnection>>;
 
-  // /// Returns a pointer to the `wl_display` object of wayland that is used by this
-  // /// `EventLoopWindowTarget`.
-  // ///
-  // /// Returns `None` if the `EventLoop` doesn't use wayland (if it uses xlib for example).
-  // ///
-  // /// The pointer will become invalid when the winit `EventLoop` is destroyed.
-  // fn wayland_display(&self) -> Option<*mut raw::c_void>;
+  /// Returns a pointer to the `wl_display` object of wayland that is used by this
+  /// `EventLoopWindowTarget`.
+  ///
+  /// Returns `None` if the `EventLoop` doesn't use wayland (if it uses xlib for example).
+  ///
+  /// The pointer will become invalid when the tao `EventLoop` is destroyed.
+  fn wayland_display(&self) -> Option<*mut c_void>;
 
   /// Returns the gtk application for this event loop.
   fn gtk_app(&self) -> &gtk::Application;
 
   /// Sets the badge count on the taskbar
   fn set_badge_count(&self, count: Option<i64>, desktop_filename: Option<String>);
+
+  /// Registers a system-wide hotkey, emitting [`Event::GlobalHotKey`] with the returned id when
+  /// it's pressed, even if no window of the app is focused.
+  ///
+  /// The hotkey is pressed whatever the state of Caps Lock and Num Lock. Returns an
+  /// [`ExternalError::Os`] if the key can't be grabbed, e.g. when another application already
+  /// registered the same hotkey.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported, returns [`ExternalError::NotSupported`].
+  fn register_global_hotkey(
+    &self,
+    accelerator: Accelerator,
+  ) -> Result<GlobalHotKeyId, ExternalError>;
+
+  /// Unregisters a hotkey registered with
+  /// [`EventLoopWindowTargetExtUnix::register_global_hotkey`]. Does nothing if it's not
+  /// registered.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Wayland:** Unsupported, returns [`ExternalError::NotSupported`].
+  fn unregister_global_hotkey(&self, id: GlobalHotKeyId) -> Result<(), ExternalError>;
 }
 
 impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
@@ -234,26 +594,16 @@ impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
   #[inline]
   fn xlib_xconnection(&self) -> Option<Arc<XConnection>> {
     if self.is_x11() {
-      if let Ok(xconn) = XConnection::new(Some(x_error_callback)) {
-        Some(Arc::new(xconn))
-      } else {
-        None
-      }
+      x11::xconnection()
     } else {
       None
     }
   }
 
-  // #[inline]
-  // fn wayland_display(&self) -> Option<*mut raw::c_void> {
-  //     match self.p {
-  //         LinuxEventLoopWindowTarget::Wayland(ref p) => {
-  //             Some(p.display().get_display_ptr() as *mut _)
-  //         }
-  //         #[cfg(feature = "x11")]
-  //         _ => None,
-  //     }
-  // }
+  #[inline]
+  fn wayland_display(&self) -> Option<*mut c_void> {
+    self.p.wayland_display()
+  }
 
   #[inline]
   fn gtk_app(&self) -> &gtk::Application {
@@ -264,24 +614,64 @@ impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
   fn set_badge_count(&self, count: Option<i64>, desktop_filename: Option<String>) {
     self.p.set_badge_count(count, desktop_filename);
   }
+
+  #[inline]
+  fn register_global_hotkey(
+    &self,
+    accelerator: Accelerator,
+  ) -> Result<GlobalHotKeyId, ExternalError> {
+    self.p.register_global_hotkey(accelerator)
+  }
+
+  #[inline]
+  fn unregister_global_hotkey(&self, id: GlobalHotKeyId) -> Result<(), ExternalError> {
+    self.p.unregister_global_hotkey(id)
+  }
 }
 
-unsafe extern "C" fn x_error_callback(
-  _display: *mut x11::ffi::Display,
-  event: *mut x11::ffi::XErrorEvent,
-) -> c_int {
-  let error = XError {
-    // TODO get the error text as description
-    description: String::new(),
-    error_code: (*event).error_code,
-    request_code: (*event).request_code,
-    minor_code: (*event).minor_code,
-  };
-
-  error!("X11 error: {:#?}", error);
-
-  // Fun fact: this return value is completely ignored.
-  0
+/// Additional methods on `EventLoopProxy` that are specific to Unix.
+pub trait EventLoopProxyExtUnix<T: 'static> {
+  /// Sends a window event to the event loop as if it came from the system, e.g. to test event
+  /// handling.
+  ///
+  /// Returns an `Err` with the event if the associated `EventLoop` no longer exists.
+  fn send_window_event(
+    &self,
+    window_id: WindowId,
+    event: WindowEvent<'static>,
+  ) -> Result<(), EventLoopClosed<Event<'static, T>>>;
+
+  /// Sends a device event to the event loop as if it came from the system, e.g. to test event
+  /// handling.
+  ///
+  /// Returns an `Err` with the event if the associated `EventLoop` no longer exists.
+  fn send_device_event(
+    &self,
+    device_id: DeviceId,
+    event: DeviceEvent,
+  ) -> Result<(), EventLoopClosed<Event<'static, T>>>;
+}
+
+impl<T: 'static> EventLoopProxyExtUnix<T> for EventLoopProxy<T> {
+  fn send_window_event(
+    &self,
+    window_id: WindowId,
+    event: WindowEvent<'static>,
+  ) -> Result<(), EventLoopClosed<Event<'static, T>>> {
+    self
+      .event_loop_proxy
+      .send_nonuser_event(Event::WindowEvent { window_id, event })
+  }
+
+  fn send_device_event(
+    &self,
+    device_id: DeviceId,
+    event: DeviceEvent,
+  ) -> Result<(), EventLoopClosed<Event<'static, T>>> {
+    self
+      .event_loop_proxy
+      .send_nonuser_event(Event::DeviceEvent { device_id, event })
+  }
 }
 
 /// Additional methods on `MonitorHandle` that are specific to Unix.
@@ -296,3 +686,53 @@ impl MonitorHandleExtUnix for MonitorHandle {
     &self.inner.monitor
   }
 }
+
+/// Additional methods on `DeviceId` that are specific to Unix.
+///
+/// These query gtk, so they must be called on the thread running the event loop.
+pub trait DeviceIdExtUnix {
+  /// Returns the gdk handle of the device.
+  ///
+  /// Will return `None` if the device is no longer available, or when called on another thread
+  /// than the event loop's.
+  fn gdk_device(&self) -> Option<gtk::gdk::Device>;
+
+  /// Returns the name of the device, e.g. `"Logitech USB Receiver"`.
+  fn name(&self) -> Option<String>;
+
+  /// Returns the vendor id of the device as a four digit hexadecimal string, e.g. `"046d"`.
+  ///
+  /// Only available for physical devices.
+  fn vendor_id(&self) -> Option<String>;
+
+  /// Returns the product id of the device as a four digit hexadecimal string, e.g. `"c52b"`.
+  ///
+  /// Only available for physical devices.
+  fn product_id(&self) -> Option<String>;
+
+  /// Returns the type of the device, e.g. a mouse, touchpad or keyboard.
+  fn input_source(&self) -> Option<gtk::gdk::InputSource>;
+}
+
+impl DeviceIdExtUnix for DeviceId {
+  #[inline]
+  fn gdk_device(&self) -> Option<gtk::gdk::Device> {
+    self.0.gdk_device()
+  }
+
+  fn name(&self) -> Option<String> {
+    self.gdk_device()?.name().map(Into::into)
+  }
+
+  fn vendor_id(&self) -> Option<String> {
+    self.gdk_device()?.vendor_id().map(Into::into)
+  }
+
+  fn product_id(&self) -> Option<String> {
+    self.gdk_device()?.product_id().map(Into::into)
+  }
+
+  fn input_source(&self) -> Option<gtk::gdk::InputSource> {
+    self.gdk_device().map(|device| device.source())
+  }
+}
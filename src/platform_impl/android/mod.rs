@@ -685,6 +685,8 @@ impl Window {
 
   pub fn set_background_color(&self, _color: Option<crate::window::RGBA>) {}
 
+  pub fn center_on(&self, _target: crate::window::CenterOn) {}
+
   pub fn set_ignore_cursor_events(&self, _ignore: bool) -> Result<(), error::ExternalError> {
     Err(error::ExternalError::NotSupported(
       error::NotSupportedError::new(),
@@ -828,6 +830,22 @@ impl MonitorHandle {
       .unwrap_or(1.0)
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    None
+  }
+
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    None
+  }
+
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    (self.position(), self.size())
+  }
+
+  pub fn stable_id(&self) -> Option<String> {
+    None
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = monitor::VideoMode> {
     let size = self.size().into();
     let mut v = Vec::new();
@@ -238,6 +238,28 @@ impl Inner {
     }
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    unsafe {
+      if !app_state::os_capabilities().maximum_frames_per_second {
+        return None;
+      }
+      let refresh_rate: NSInteger = msg_send![self.uiscreen, maximumFramesPerSecond];
+      Some(refresh_rate as u32 * 1000)
+    }
+  }
+
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    None
+  }
+
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    (self.position(), self.size())
+  }
+
+  pub fn stable_id(&self) -> Option<String> {
+    None
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     let mut modes = BTreeSet::new();
     unsafe {
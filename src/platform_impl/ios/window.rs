@@ -365,6 +365,8 @@ impl Inner {
 
   pub fn set_background_color(&self, _color: Option<crate::window::RGBA>) {}
 
+  pub fn center_on(&self, _target: crate::window::CenterOn) {}
+
   // Allow directly accessing the current monitor internally without unwrapping.
   fn current_monitor_inner(&self) -> RootMonitorHandle {
     unsafe {
@@ -1,30 +1,46 @@
 use std::{
   os::raw::{c_int, c_uchar},
-  ptr,
+  ptr, slice,
 };
 
 use gtk::glib;
 use x11_dl::{xinput2, xlib};
 
-use crate::event::{DeviceEvent, ElementState, RawKeyEvent};
+use crate::event::{DeviceEvent, ElementState, MouseScrollDelta, RawKeyEvent};
 
-use super::keycode_from_scancode;
+use super::{keycode_from_scancode, DeviceId};
 
 /// Spawn Device event thread. Only works on x11 since wayland doesn't have such global events.
-pub fn spawn(device_tx: glib::Sender<DeviceEvent>) {
+pub fn spawn(device_tx: glib::Sender<(DeviceId, DeviceEvent)>) {
   std::thread::spawn(move || unsafe {
     let xlib = xlib::Xlib::open().unwrap();
     let xinput2 = xinput2::XInput2::open().unwrap();
     let display = (xlib.XOpenDisplay)(ptr::null());
     let root = (xlib.XDefaultRootWindow)(display);
-    // TODO Add more device event mask
-    let mask = xinput2::XI_RawKeyPressMask | xinput2::XI_RawKeyReleaseMask;
-    let mut event_mask = xinput2::XIEventMask {
-      deviceid: xinput2::XIAllMasterDevices,
-      mask: &mask as *const _ as *mut c_uchar,
-      mask_len: std::mem::size_of_val(&mask) as c_int,
-    };
-    (xinput2.XISelectEvents)(display, root, &mut event_mask as *mut _, 1);
+    let mask = xinput2::XI_RawKeyPressMask
+      | xinput2::XI_RawKeyReleaseMask
+      | xinput2::XI_RawButtonPressMask
+      | xinput2::XI_RawButtonReleaseMask
+      | xinput2::XI_RawMotionMask;
+    let hierarchy_mask = xinput2::XI_HierarchyChangedMask;
+    let mut event_masks = [
+      xinput2::XIEventMask {
+        deviceid: xinput2::XIAllMasterDevices,
+        mask: &mask as *const _ as *mut c_uchar,
+        mask_len: std::mem::size_of_val(&mask) as c_int,
+      },
+      xinput2::XIEventMask {
+        deviceid: xinput2::XIAllDevices,
+        mask: &hierarchy_mask as *const _ as *mut c_uchar,
+        mask_len: std::mem::size_of_val(&hierarchy_mask) as c_int,
+      },
+    ];
+    (xinput2.XISelectEvents)(
+      display,
+      root,
+      event_masks.as_mut_ptr(),
+      event_masks.len() as c_int,
+    );
 
     #[allow(clippy::uninit_assumed_init)]
     let mut event: xlib::XEvent = std::mem::MaybeUninit::uninit().assume_init();
@@ -44,6 +60,7 @@ pub fn spawn(device_tx: glib::Sender<DeviceEvent>) {
         continue;
       }
 
+      let mut events = Vec::new();
       let event_type = event.get_type();
       match event_type {
         xlib::GenericEvent => {
@@ -64,17 +81,100 @@ pub fn spawn(device_tx: glib::Sender<DeviceEvent>) {
                   state,
                 };
 
-                if let Err(e) = device_tx.send(DeviceEvent::Key(event)) {
-                  log::info!("Failed to send device event {} since receiver is closed. Closing x11 thread along with it", e);
-                  break;
+                events.push((DeviceId::from_xinput(xev.sourceid), DeviceEvent::Key(event)));
+              }
+              xinput2::XI_RawButtonPress | xinput2::XI_RawButtonRelease => {
+                let xev: &xinput2::XIRawEvent = &*(xev.data as *const _);
+                let device_id = DeviceId::from_xinput(xev.sourceid);
+                let state = match xev.evtype {
+                  xinput2::XI_RawButtonPress => ElementState::Pressed,
+                  xinput2::XI_RawButtonRelease => ElementState::Released,
+                  _ => unreachable!(),
+                };
+
+                // Buttons 4 to 7 are the legacy scroll wheel buttons, only report their press.
+                match xev.detail {
+                  4..=7 if state == ElementState::Pressed => {
+                    let delta = match xev.detail {
+                      4 => (0.0, 1.0),
+                      5 => (0.0, -1.0),
+                      6 => (1.0, 0.0),
+                      _ => (-1.0, 0.0),
+                    };
+                    events.push((
+                      device_id,
+                      DeviceEvent::MouseWheel {
+                        delta: MouseScrollDelta::LineDelta(delta.0, delta.1),
+                      },
+                    ));
+                  }
+                  4..=7 => {}
+                  button => events.push((
+                    device_id,
+                    DeviceEvent::Button {
+                      button: button as u32,
+                      state,
+                    },
+                  )),
+                }
+              }
+              xinput2::XI_RawMotion => {
+                let xev: &xinput2::XIRawEvent = &*(xev.data as *const _);
+                let device_id = DeviceId::from_xinput(xev.sourceid);
+                let mask =
+                  slice::from_raw_parts(xev.valuators.mask, xev.valuators.mask_len as usize);
+                let mut values = xev.raw_values;
+                let mut delta = (0.0, 0.0);
+                for axis in 0..(mask.len() * 8) {
+                  if !xinput2::XIMaskIsSet(mask, axis as i32) {
+                    continue;
+                  }
+                  let value = *values;
+                  values = values.offset(1);
+
+                  match axis {
+                    0 => delta.0 = value,
+                    1 => delta.1 = value,
+                    _ => {}
+                  }
+                  events.push((
+                    device_id,
+                    DeviceEvent::Motion {
+                      axis: axis as u32,
+                      value,
+                    },
+                  ));
+                }
+                if delta != (0.0, 0.0) {
+                  events.push((device_id, DeviceEvent::MouseMotion { delta }));
+                }
+              }
+              xinput2::XI_HierarchyChanged => {
+                let xev: &xinput2::XIHierarchyEvent = &*(xev.data as *const _);
+                let infos = slice::from_raw_parts(xev.info, xev.num_info as usize);
+                for info in infos {
+                  let device_id = DeviceId::from_xinput(info.deviceid);
+                  if info.flags & (xinput2::XISlaveAdded | xinput2::XIMasterAdded) != 0 {
+                    events.push((device_id, DeviceEvent::Added));
+                  } else if info.flags & (xinput2::XISlaveRemoved | xinput2::XIMasterRemoved) != 0 {
+                    events.push((device_id, DeviceEvent::Removed));
+                  }
                 }
               }
               _ => {}
             }
+            (xlib.XFreeEventData)(display, &mut xev);
           }
         }
         _ => {}
       }
+
+      for event in events {
+        if let Err(e) = device_tx.send(event) {
+          log::info!("Failed to send device event {:?} since receiver is closed. Closing x11 thread along with it", e);
+          return;
+        }
+      }
     }
   });
 }
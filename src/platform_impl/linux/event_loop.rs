@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-  cell::RefCell,
-  collections::{HashSet, VecDeque},
-  error::Error,
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet, VecDeque},
+  env, fs,
+  path::PathBuf,
   process,
   rc::Rc,
   sync::atomic::{AtomicBool, Ordering},
@@ -25,17 +26,21 @@ use gtk::{
 };
 
 use crate::{
-  dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
-  error::ExternalError,
+  accelerator::{Accelerator, GlobalHotKeyId},
+  dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
+  error::{ExternalError, NotSupportedError},
   event::{
-    ElementState, Event, MouseButton, MouseScrollDelta, StartCause, TouchPhase, WindowEvent,
+    DeviceEvent, DeviceId as RootDeviceId, ElementState, Event, MonitorEvent, MouseButton,
+    MouseScrollDelta, StartCause, TouchPhase, WindowEvent,
   },
-  event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
+  event_loop::{ControlFlow, DeviceEventFilter, EventLoopClosed, EventLoopWindowTarget as RootELW},
   keyboard::ModifiersState,
   monitor::MonitorHandle as RootMonitorHandle,
-  platform_impl::platform::{device, DEVICE_ID},
+  platform::unix::OsErrorKind,
+  platform_impl::platform::{device, device_id},
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, WindowId as RootWindowId,
+    CenterOn, CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme,
+    WindowId as RootWindowId,
   },
 };
 
@@ -43,11 +48,19 @@ use super::{
   keyboard,
   monitor::{self, MonitorHandle},
   taskbar, util,
-  window::{WindowId, WindowRequest},
+  window::{theme_from_settings, WindowId, WindowRequest},
+  x11::{self, ffi, hotkey::GlobalHotKeys},
+  OsError,
 };
 
 use taskbar::TaskbarIndicator;
 
+/// Video modes to restore when windows leave exclusive fullscreen, by window.
+pub(crate) type ExclusiveModes = Rc<RefCell<HashMap<WindowId, (ffi::RRCrtc, ffi::RRMode)>>>;
+
+/// Last known cursor position on Wayland, relative to the window containing the cursor.
+pub(crate) type CursorPosition = Rc<Cell<Option<(WindowId, PhysicalPosition<f64>)>>>;
+
 #[derive(Clone)]
 pub struct EventLoopWindowTarget<T> {
   /// Gdk display
@@ -60,6 +73,14 @@ pub struct EventLoopWindowTarget<T> {
   pub(crate) window_requests_tx: glib::Sender<(WindowId, WindowRequest)>,
   /// Draw event sender
   pub(crate) draw_tx: crossbeam_channel::Sender<WindowId>,
+  /// Device event filter
+  pub(crate) device_event_filter: Rc<Cell<DeviceEventFilter>>,
+  /// Cursor position on Wayland
+  pub(crate) cursor_position: CursorPosition,
+  /// Global hotkeys grabbed on X11
+  pub(crate) global_hotkeys: Rc<RefCell<GlobalHotKeys>>,
+  /// Video modes changed by windows in exclusive fullscreen
+  pub(crate) exclusive_modes: ExclusiveModes,
   _marker: std::marker::PhantomData<T>,
 }
 
@@ -145,9 +166,59 @@ impl<T> EventLoopWindowTarget<T> {
     self.display.backend().is_x11()
   }
 
+  pub fn wayland_display(&self) -> Option<*mut std::ffi::c_void> {
+    self.is_wayland().then(|| unsafe {
+      gdk_wayland_sys::gdk_wayland_display_get_wl_display(self.display.as_ptr() as *mut _)
+    })
+  }
+
+  #[inline]
+  pub fn set_device_event_filter(&self, filter: DeviceEventFilter) {
+    self.device_event_filter.set(filter);
+  }
+
   #[inline]
   pub fn cursor_position(&self) -> Result<PhysicalPosition<f64>, ExternalError> {
-    util::cursor_position(self.is_wayland())
+    if self.is_wayland() {
+      self
+        .cursor_position
+        .get()
+        .map(|(_, position)| position)
+        .ok_or(ExternalError::NotSupported(NotSupportedError::new()))
+    } else {
+      util::cursor_position()
+    }
+  }
+
+  pub fn register_global_hotkey(
+    &self,
+    accelerator: Accelerator,
+  ) -> Result<GlobalHotKeyId, ExternalError> {
+    if !self.is_x11() {
+      return Err(ExternalError::NotSupported(NotSupportedError::new()));
+    }
+    let Some(xconn) = x11::xconnection() else {
+      return Err(ExternalError::Os(os_error!(OsError::new(
+        OsErrorKind::NoDisplay,
+        "Failed to open connection to X server"
+      ))));
+    };
+
+    self
+      .global_hotkeys
+      .borrow_mut()
+      .register(&xconn, accelerator)
+      .map_err(|e| ExternalError::Os(os_error!(e)))
+  }
+
+  pub fn unregister_global_hotkey(&self, id: GlobalHotKeyId) -> Result<(), ExternalError> {
+    if !self.is_x11() {
+      return Err(ExternalError::NotSupported(NotSupportedError::new()));
+    }
+    if let Some(xconn) = x11::opened_xconnection() {
+      self.global_hotkeys.borrow_mut().unregister(xconn, id);
+    }
+    Ok(())
   }
 
   #[inline]
@@ -190,6 +261,9 @@ pub struct EventLoop<T: 'static> {
   events: crossbeam_channel::Receiver<Event<'static, T>>,
   /// Draw queue of EventLoop
   draws: crossbeam_channel::Receiver<WindowId>,
+  /// Scale factor changes of EventLoop, which can't go through the event queue since
+  /// `ScaleFactorChanged` borrows the new window size.
+  scale_factor_changes: crossbeam_channel::Receiver<(WindowId, f64)>,
   /// Boolean to control device event thread
   run_device_thread: Option<Rc<AtomicBool>>,
 }
@@ -198,6 +272,7 @@ pub struct EventLoop<T: 'static> {
 pub(crate) struct PlatformSpecificEventLoopAttributes {
   pub(crate) any_thread: bool,
   pub(crate) app_id: Option<String>,
+  pub(crate) single_instance: bool,
 }
 
 impl<T: 'static> EventLoop<T> {
@@ -206,61 +281,183 @@ impl<T: 'static> EventLoop<T> {
       assert_is_main_thread("new_any_thread");
     }
 
+    match Self::try_new(attrs) {
+      Ok(event_loop) => event_loop,
+      // The primary instance handles the command line, this one has nothing left to do.
+      Err(e) if e.kind == OsErrorKind::SecondInstance => process::exit(0),
+      Err(e) => panic!("Failed to initialize gtk backend!: {:?}", e),
+    }
+  }
+
+  /// Like [`EventLoop::new`], but returns an error instead of panicking when gtk can't be
+  /// initialized, e.g. without a display server.
+  pub(crate) fn try_new(
+    attrs: &PlatformSpecificEventLoopAttributes,
+  ) -> Result<EventLoop<T>, OsError> {
+    if !attrs.any_thread && !is_main_thread() {
+      return Err(OsError::new(
+        OsErrorKind::Other,
+        "The event loop must be created on the main thread, see `EventLoopBuilderExtUnix::with_any_thread`",
+      ));
+    }
+
     let context = MainContext::default();
     context
-      .with_thread_default(|| {
-        EventLoop::new_gtk(attrs.app_id.as_deref()).expect("Failed to initialize gtk backend!")
-      })
-      .expect("Failed to initialize gtk backend!")
+      .with_thread_default(|| EventLoop::new_gtk(attrs.app_id.as_deref(), attrs.single_instance))
+      .map_err(|e| OsError::new(OsErrorKind::GtkInit, e.to_string()))?
   }
 
-  fn new_gtk(app_id: Option<&str>) -> Result<EventLoop<T>, Box<dyn Error>> {
+  fn new_gtk(app_id: Option<&str>, single_instance: bool) -> Result<EventLoop<T>, OsError> {
     // This should be done by gtk::Application::new, but does not work properly
-    gtk::init()?;
+    if let Err(e) = gtk::init() {
+      // gtk fails to initialize when it can't open the default display. gdk can't be queried
+      // before it's initialized, so tell whether a display was configured at all.
+      let kind = if env::var_os("DISPLAY").is_none() && env::var_os("WAYLAND_DISPLAY").is_none() {
+        OsErrorKind::NoDisplay
+      } else {
+        OsErrorKind::GtkInit
+      };
+      return Err(OsError::new(kind, e.to_string()));
+    }
     let context = MainContext::default();
-    let app = gtk::Application::new(app_id, gio::ApplicationFlags::empty());
+    let mut flags = gio::ApplicationFlags::empty();
+    // Files are only opened through D-Bus for apps with an id.
+    if app_id.is_some() {
+      flags |= gio::ApplicationFlags::HANDLES_OPEN;
+    }
+    if single_instance {
+      flags |= gio::ApplicationFlags::HANDLES_COMMAND_LINE;
+    }
+    let app = gtk::Application::new(app_id, flags);
     let app_ = app.clone();
     let cancellable: Option<&Cancellable> = None;
-    app.register(cancellable)?;
+    app
+      .register(cancellable)
+      .map_err(|e| OsError::new(OsErrorKind::GtkInit, e.to_string()))?;
+
+    // Forward the command line to the primary instance, which emits `Event::SecondInstance`.
+    if single_instance && app.is_remote() {
+      let args = std::env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+      app.run_with_args(&args);
+      return Err(OsError::new(
+        OsErrorKind::SecondInstance,
+        "The command line was forwarded to the primary instance",
+      ));
+    }
 
     // Send StartCause::Init event
     let (event_tx, event_rx) = crossbeam_channel::unbounded();
     let (draw_tx, draw_rx) = crossbeam_channel::unbounded();
+    let (scale_factor_tx, scale_factor_rx) = crossbeam_channel::unbounded();
     let event_tx_ = event_tx.clone();
+    // The files the app is launched with don't go through `open`, report them once it starts.
+    let launch_urls = Cell::new(Some(launch_urls()));
     app.connect_activate(move |_| {
       if let Err(e) = event_tx_.send(Event::NewEvents(StartCause::Init)) {
         log::warn!("Failed to send init event to event channel: {}", e);
       }
+      if let Some(urls) = launch_urls.take().filter(|urls| !urls.is_empty()) {
+        if let Err(e) = event_tx_.send(Event::Opened { urls }) {
+          log::warn!("Failed to send opened event to event channel: {}", e);
+        }
+      }
+    });
+    let event_tx_ = event_tx.clone();
+    app.connect_open(move |_, files, _| {
+      let urls = files
+        .iter()
+        .filter_map(|file| url::Url::parse(&file.uri()).ok())
+        .collect();
+      if let Err(e) = event_tx_.send(Event::Opened { urls }) {
+        log::warn!("Failed to send opened event to event channel: {}", e);
+      }
+    });
+    let event_tx_ = event_tx.clone();
+    app.connect_command_line(move |_, command_line| {
+      let args = command_line
+        .arguments()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+      let cwd = command_line.cwd().unwrap_or_default();
+      if let Err(e) = event_tx_.send(Event::SecondInstance { args, cwd }) {
+        log::warn!(
+          "Failed to send second instance event to event channel: {}",
+          e
+        );
+      }
+      0
     });
     let draw_tx_ = draw_tx.clone();
     let user_event_tx = event_tx.clone();
+    let event_tx_ = event_tx.clone();
+    let global_hotkeys = GlobalHotKeys::new(move |id| {
+      if let Err(e) = event_tx_.send(Event::GlobalHotKey(id)) {
+        log::warn!("Failed to send global hotkey event to event channel: {}", e);
+      }
+    });
 
     // Create event loop window target.
     let (window_requests_tx, window_requests_rx) = glib::MainContext::channel(Priority::default());
     let display = gdk::Display::default()
-      .expect("GdkDisplay not found. This usually means `gkt_init` hasn't called yet.");
+      .ok_or_else(|| OsError::new(OsErrorKind::NoDisplay, "No default display found"))?;
     let window_target = EventLoopWindowTarget {
       display,
       app,
       windows: Rc::new(RefCell::new(HashSet::new())),
       window_requests_tx,
       draw_tx: draw_tx_,
+      // Device events were always reported on Linux, keep doing so unless asked otherwise.
+      device_event_filter: Rc::new(Cell::new(DeviceEventFilter::Never)),
+      cursor_position: Default::default(),
+      global_hotkeys: Rc::new(RefCell::new(global_hotkeys)),
+      exclusive_modes: Default::default(),
       _marker: std::marker::PhantomData,
     };
 
+    // Report monitors being connected, disconnected or reconfigured.
+    let display = &window_target.display;
+    for i in 0..display.n_monitors() {
+      if let Some(monitor) = display.monitor(i) {
+        connect_monitor_changed(&monitor, &event_tx);
+      }
+    }
+    let tx_clone = event_tx.clone();
+    display.connect_monitor_added(move |_, monitor| {
+      connect_monitor_changed(monitor, &tx_clone);
+      send_monitor_event(&tx_clone, monitor, MonitorEvent::Added);
+    });
+    let tx_clone = event_tx.clone();
+    display.connect_monitor_removed(move |_, monitor| {
+      send_monitor_event(&tx_clone, monitor, MonitorEvent::Removed);
+    });
+
     // Spawn x11 thread to receive Device events.
     let run_device_thread = if window_target.is_x11() {
       let (device_tx, device_rx) = glib::MainContext::channel(glib::Priority::default());
       let user_event_tx = user_event_tx.clone();
       let run_device_thread = Rc::new(AtomicBool::new(true));
       let run = run_device_thread.clone();
+      let filter = window_target.device_event_filter.clone();
+      let app = window_target.app.clone();
       device::spawn(device_tx);
-      device_rx.attach(Some(&context), move |event| {
-        if let Err(e) = user_event_tx.send(Event::DeviceEvent {
-          device_id: DEVICE_ID,
-          event,
-        }) {
-          log::warn!("Fail to send device event to event channel: {}", e);
+      device_rx.attach(Some(&context), move |(device_id, event)| {
+        // Devices being added or removed are always reported, only input is filtered.
+        let filtered = match (&event, filter.get()) {
+          (DeviceEvent::Added | DeviceEvent::Removed, _) => false,
+          (_, DeviceEventFilter::Always) => true,
+          (_, DeviceEventFilter::Unfocused) => !app.windows().iter().any(|w| w.is_active()),
+          (_, DeviceEventFilter::Never) => false,
+        };
+        if !filtered {
+          if let Err(e) = user_event_tx.send(Event::DeviceEvent {
+            device_id: RootDeviceId(device_id),
+            event,
+          }) {
+            log::warn!("Fail to send device event to event channel: {}", e);
+          }
         }
         if run.load(Ordering::Relaxed) {
           glib::ControlFlow::Continue
@@ -273,15 +470,47 @@ impl<T: 'static> EventLoop<T> {
       None
     };
 
+    let cursor_position = window_target.cursor_position.clone();
     let mut taskbar = TaskbarIndicator::new();
     let is_wayland = window_target.is_wayland();
+    let is_x11 = window_target.is_x11();
+    let exclusive_modes = window_target.exclusive_modes.clone();
 
     // Window Request
     window_requests_rx.attach(Some(&context), move |(id, request)| {
+      if let WindowRequest::Fullscreen(_) = request {
+        restore_video_mode(&exclusive_modes, id);
+      }
+
       if let Some(window) = app_.window_by_id(id.0) {
         match request {
           WindowRequest::Title(title) => window.set_title(&title),
           WindowRequest::Position((x, y)) => window.move_(x, y),
+          WindowRequest::CenterOn(target) => {
+            let area = match target {
+              CenterOn::Parent => window.transient_for().map(|parent| {
+                let (x, y) = parent.position();
+                let (width, height) = parent.size();
+                gdk::Rectangle::new(x, y, width, height)
+              }),
+              CenterOn::Monitor(monitor) => Some(monitor.inner.monitor.workarea()),
+            }
+            .or_else(|| {
+              let display = window.display();
+              window
+                .window()
+                .and_then(|gdk_window| display.monitor_at_window(&gdk_window))
+                .or_else(|| display.primary_monitor())
+                .map(|monitor| monitor.workarea())
+            });
+            if let Some(area) = area {
+              let (width, height) = window.size();
+              window.move_(
+                area.x() + (area.width() - width) / 2,
+                area.y() + (area.height() - height) / 2,
+              );
+            }
+          }
           WindowRequest::Size((w, h)) => window.resize(w, h),
           WindowRequest::SizeConstraints(constraints) => {
             util::set_size_constraints(&window, constraints);
@@ -297,6 +526,12 @@ impl<T: 'static> EventLoop<T> {
             window.present_with_time(gdk::ffi::GDK_CURRENT_TIME as _);
           }
           WindowRequest::Resizable(resizable) => window.set_resizable(resizable),
+          WindowRequest::Minimizable(minimizable) => {
+            util::set_window_buttons(&window, Some(minimizable), None)
+          }
+          WindowRequest::Maximizable(maximizable) => {
+            util::set_window_buttons(&window, None, Some(maximizable))
+          }
           WindowRequest::Closable(closable) => window.set_deletable(closable),
           WindowRequest::Minimized(minimized) => {
             if minimized {
@@ -344,21 +579,42 @@ impl<T: 'static> EventLoop<T> {
           }
           WindowRequest::Fullscreen(fullscreen) => match fullscreen {
             Some(f) => {
-              if let Fullscreen::Borderless(m) = f {
-                if let Some(monitor) = m {
-                  let display = window.display();
-                  let monitor = monitor.inner;
-                  let monitors = display.n_monitors();
-                  for i in 0..monitors {
-                    let m = display.monitor(i).unwrap();
-                    if m == monitor.monitor {
-                      let screen = display.default_screen();
-                      window.fullscreen_on_monitor(&screen, i);
+              let monitor = match f {
+                Fullscreen::Borderless(m) => m.map(|m| m.inner),
+                Fullscreen::Exclusive(video_mode) => {
+                  let video_mode = video_mode.video_mode;
+                  if is_x11 {
+                    if let Some(xconn) = x11::xconnection() {
+                      if let Some(crtc) = xconn.crtc_at(video_mode.monitor.position().into()) {
+                        if crtc.current_mode != video_mode.native_mode {
+                          match xconn.set_crtc_mode(crtc.id, video_mode.native_mode) {
+                            Ok(()) => {
+                              exclusive_modes
+                                .borrow_mut()
+                                .insert(id, (crtc.id, crtc.current_mode));
+                            }
+                            Err(e) => log::warn!("Failed to change the video mode: {}", e),
+                          }
+                        }
+                      }
                     }
                   }
-                } else {
-                  window.fullscreen();
+                  Some(video_mode.monitor)
+                }
+              };
+
+              if let Some(monitor) = monitor {
+                let display = window.display();
+                let monitors = display.n_monitors();
+                for i in 0..monitors {
+                  let m = display.monitor(i).unwrap();
+                  if m == monitor.monitor {
+                    let screen = display.default_screen();
+                    window.fullscreen_on_monitor(&screen, i);
+                  }
                 }
+              } else {
+                window.fullscreen();
               }
             }
             None => window.unfullscreen(),
@@ -380,6 +636,13 @@ impl<T: 'static> EventLoop<T> {
             window.set_skip_taskbar_hint(skip);
             window.set_skip_pager_hint(skip)
           }
+          WindowRequest::SkipPager(skip) => window.set_skip_pager_hint(skip),
+          WindowRequest::WindowType(window_type) => {
+            window.set_type_hint(util::window_type_hint(window_type))
+          }
+          WindowRequest::OverrideRedirect(override_redirect) => {
+            util::set_override_redirect(&window, override_redirect)
+          }
           WindowRequest::BackgroundColor(css_provider, color) => {
             unsafe { window.set_data("background_color", color) };
 
@@ -432,6 +695,21 @@ impl<T: 'static> EventLoop<T> {
               }
             }
           }
+          WindowRequest::ImePosition((x, y)) => {
+            if let Some(ime) = unsafe { window.data::<gtk::IMMulticontext>("ime") } {
+              // The position is relative to the client area while gtk expects it relative to
+              // the gdk window, which also contains the client-side decorations.
+              let (offset_x, offset_y) = window
+                .child()
+                .map(|c| {
+                  let rect = c.allocation();
+                  (rect.x(), rect.y())
+                })
+                .unwrap_or_default();
+              let area = gdk::Rectangle::new(x + offset_x, y + offset_y, 0, 0);
+              unsafe { ime.as_ref() }.set_cursor_location(&area);
+            }
+          }
           WindowRequest::CursorIgnoreEvents(ignore) => {
             if ignore {
               let empty_region = Region::create_rectangle(&RectangleInt::new(0, 0, 1, 1));
@@ -463,13 +741,16 @@ impl<T: 'static> EventLoop<T> {
 
             let fullscreen = Rc::new(AtomicBool::new(fullscreen));
             let fullscreen_ = fullscreen.clone();
+            let exclusive_modes_ = exclusive_modes.clone();
             window.connect_window_state_event(move |_window, event| {
               let state = event.changed_mask();
               if state.contains(WindowState::FULLSCREEN) {
-                fullscreen_.store(
-                  event.new_window_state().contains(WindowState::FULLSCREEN),
-                  Ordering::Relaxed,
-                );
+                let is_fullscreen = event.new_window_state().contains(WindowState::FULLSCREEN);
+                fullscreen_.store(is_fullscreen, Ordering::Relaxed);
+                // The window manager can leave fullscreen on its own, e.g. on a shortcut.
+                if !is_fullscreen {
+                  restore_video_mode(&exclusive_modes_, id);
+                }
               }
               glib::Propagation::Proceed
             });
@@ -620,6 +901,45 @@ impl<T: 'static> EventLoop<T> {
               false
             });
 
+            let scale_factor_tx = scale_factor_tx.clone();
+            window.connect_scale_factor_notify(move |window| {
+              if let Err(e) = scale_factor_tx.send((id, window.scale_factor() as f64)) {
+                log::warn!(
+                  "Failed to send scale factor changed event to event channel: {}",
+                  e
+                );
+              }
+            });
+
+            if let Some(settings) = Settings::default() {
+              let theme = Cell::new(theme_from_settings(&settings));
+              let tx_clone = event_tx.clone();
+              let on_theme_changed = Rc::new(move |settings: &Settings| {
+                let new_theme = theme_from_settings(settings);
+                if theme.replace(new_theme) != new_theme {
+                  if let Err(e) = tx_clone.send(Event::WindowEvent {
+                    window_id: RootWindowId(id),
+                    event: WindowEvent::ThemeChanged(new_theme),
+                  }) {
+                    log::warn!("Failed to send theme changed event to event channel: {}", e);
+                  }
+                }
+              });
+              let f = on_theme_changed.clone();
+              let theme_name_handler = settings.connect_gtk_theme_name_notify(move |s| f(s));
+              let f = on_theme_changed;
+              let prefer_dark_handler =
+                settings.connect_gtk_application_prefer_dark_theme_notify(move |s| f(s));
+
+              // The settings are shared by all windows, so stop listening once this one is gone.
+              let handlers = RefCell::new(vec![theme_name_handler, prefer_dark_handler]);
+              window.connect_destroy(move |_| {
+                for handler in handlers.take() {
+                  settings.disconnect(handler);
+                }
+              });
+            }
+
             let tx_clone = event_tx.clone();
             window.connect_focus_in_event(move |_, _| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
@@ -649,7 +969,9 @@ impl<T: 'static> EventLoop<T> {
             });
 
             let tx_clone = event_tx.clone();
+            let exclusive_modes_ = exclusive_modes.clone();
             window.connect_destroy(move |_| {
+              restore_video_mode(&exclusive_modes_, id);
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::Destroyed,
@@ -661,12 +983,41 @@ impl<T: 'static> EventLoop<T> {
               }
             });
 
+            // Wayland clients can't query the cursor position, so keep the last known one.
+            if is_wayland {
+              let cursor_position_ = cursor_position.clone();
+              window.connect_enter_notify_event(move |window, event| {
+                if let Some(position) = event.device().and_then(|d| client_position(window, &d)) {
+                  cursor_position_.set(Some((id, position)));
+                }
+                glib::Propagation::Proceed
+              });
+
+              let cursor_position_ = cursor_position.clone();
+              window.connect_motion_notify_event(move |window, motion| {
+                if let Some(position) = motion.device().and_then(|d| client_position(window, &d)) {
+                  cursor_position_.set(Some((id, position)));
+                }
+                glib::Propagation::Proceed
+              });
+
+              let cursor_position_ = cursor_position.clone();
+              window.connect_leave_notify_event(move |_, event| {
+                let left = event.detail() != gdk::NotifyType::Inferior;
+                if left && matches!(cursor_position_.get(), Some((window_id, _)) if window_id == id)
+                {
+                  cursor_position_.set(None);
+                }
+                glib::Propagation::Proceed
+              });
+            }
+
             let tx_clone = event_tx.clone();
-            window.connect_enter_notify_event(move |_, _| {
+            window.connect_enter_notify_event(move |_, crossing| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::CursorEntered {
-                  device_id: DEVICE_ID,
+                  device_id: device_id(crossing),
                 },
               }) {
                 log::warn!(
@@ -687,7 +1038,7 @@ impl<T: 'static> EventLoop<T> {
                     window_id: RootWindowId(id),
                     event: WindowEvent::CursorMoved {
                       position: LogicalPosition::new(x, y).to_physical(scale_factor as f64),
-                      device_id: DEVICE_ID,
+                      device_id: device_id(motion),
                       // this field is depracted so it is fine to pass empty state
                       modifiers: ModifiersState::empty(),
                     },
@@ -700,11 +1051,11 @@ impl<T: 'static> EventLoop<T> {
             });
 
             let tx_clone = event_tx.clone();
-            window.connect_leave_notify_event(move |_, _| {
+            window.connect_leave_notify_event(move |_, crossing| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::CursorLeft {
-                  device_id: DEVICE_ID,
+                  device_id: device_id(crossing),
                 },
               }) {
                 log::warn!("Failed to send cursor left event to event channel: {}", e);
@@ -725,7 +1076,7 @@ impl<T: 'static> EventLoop<T> {
                     _ => MouseButton::Other(button as u16),
                   },
                   state: ElementState::Pressed,
-                  device_id: DEVICE_ID,
+                  device_id: device_id(event),
                   // this field is depracted so it is fine to pass empty state
                   modifiers: ModifiersState::empty(),
                 },
@@ -751,7 +1102,7 @@ impl<T: 'static> EventLoop<T> {
                     _ => MouseButton::Other(button as u16),
                   },
                   state: ElementState::Released,
-                  device_id: DEVICE_ID,
+                  device_id: device_id(event),
                   // this field is depracted so it is fine to pass empty state
                   modifiers: ModifiersState::empty(),
                 },
@@ -770,7 +1121,7 @@ impl<T: 'static> EventLoop<T> {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
                 event: WindowEvent::MouseWheel {
-                  device_id: DEVICE_ID,
+                  device_id: device_id(event),
                   delta: MouseScrollDelta::LineDelta(-x as f32, -y as f32),
                   phase: match event.direction() {
                     ScrollDirection::Smooth => TouchPhase::Moved,
@@ -784,16 +1135,41 @@ impl<T: 'static> EventLoop<T> {
               glib::Propagation::Proceed
             });
 
+            // Keys currently held down, to tell auto-repeated presses apart.
+            let pressed_keys: Rc<RefCell<HashSet<u16>>> = Default::default();
+            // Modifiers currently held down, updated on every key event.
+            let modifiers: Rc<Cell<ModifiersState>> = Default::default();
+            let pressed_keys_ = pressed_keys.clone();
+            let modifiers_ = modifiers.clone();
             let tx_clone = event_tx.clone();
-            let keyboard_handler = Rc::new(move |event_key: EventKey, element_state| {
-              // if we have a modifier lets send it
-              let mut mods = keyboard::get_modifiers(event_key.clone());
-              if !mods.is_empty() {
-                // if we release the modifier tell the world
-                if ElementState::Released == element_state {
-                  mods = ModifiersState::empty();
+            window.connect_focus_out_event(move |_, _| {
+              // Releases won't be received while unfocused.
+              pressed_keys_.borrow_mut().clear();
+              if !modifiers_.take().is_empty() {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::ModifiersChanged(ModifiersState::empty()),
+                }) {
+                  log::warn!(
+                    "Failed to send modifiers changed event to event channel: {}",
+                    e
+                  );
                 }
+              }
+              glib::Propagation::Proceed
+            });
 
+            let tx_clone = event_tx.clone();
+            let keyboard_handler = Rc::new(move |event_key: EventKey, element_state| {
+              // The state of the event has the modifiers held down before it, including the
+              // ones pressed in other windows, the tracked keys only tell their sides.
+              let mut mods = keyboard::seed_modifiers(&event_key, modifiers.get());
+              let modifier = keyboard::get_modifier(&event_key);
+              if let Some(modifier) = modifier {
+                mods = keyboard::update_modifiers(mods, modifier, element_state);
+              }
+
+              if mods != modifiers.replace(mods) || modifier.is_some() {
                 if let Err(e) = tx_clone.send(Event::WindowEvent {
                   window_id: RootWindowId(id),
                   event: WindowEvent::ModifiersChanged(mods),
@@ -802,21 +1178,28 @@ impl<T: 'static> EventLoop<T> {
                     "Failed to send modifiers changed event to event channel: {}",
                     e
                   );
-                } else {
+                } else if modifier.is_some() {
                   // stop here we don't want to send the key event
                   // as we emit the `ModifiersChanged`
                   return glib::ControlFlow::Continue;
                 }
               }
 
-              // todo: implement repeat?
-              let event = keyboard::make_key_event(&event_key, false, None, element_state);
+              let keycode = event_key.hardware_keycode();
+              let is_repeat = match element_state {
+                ElementState::Pressed => !pressed_keys.borrow_mut().insert(keycode),
+                ElementState::Released => {
+                  pressed_keys.borrow_mut().remove(&keycode);
+                  false
+                }
+              };
+              let event = keyboard::make_key_event(&event_key, is_repeat, None, element_state);
 
               if let Some(event) = event {
                 if let Err(e) = tx_clone.send(Event::WindowEvent {
                   window_id: RootWindowId(id),
                   event: WindowEvent::KeyboardInput {
-                    device_id: DEVICE_ID,
+                    device_id: device_id(&event_key),
                     event,
                     is_synthetic: false,
                   },
@@ -827,11 +1210,31 @@ impl<T: 'static> EventLoop<T> {
               glib::ControlFlow::Continue
             });
 
-            let tx_clone = event_tx.clone();
-            // TODO Add actual IME from system
-            let ime = gtk::IMContextSimple::default();
+            // Use the input method selected by the system (ibus, fcitx, ...)
+            let ime = gtk::IMMulticontext::new();
             ime.set_client_window(window.window().as_ref());
             ime.focus_in();
+            // Keep it around to be able to update the candidate box position later.
+            unsafe { window.set_data("ime", ime.clone()) };
+
+            let ime_ = ime.clone();
+            window.connect_realize(move |window| {
+              ime_.set_client_window(window.window().as_ref());
+            });
+
+            let ime_ = ime.clone();
+            window.connect_focus_in_event(move |_, _| {
+              ime_.focus_in();
+              glib::Propagation::Proceed
+            });
+
+            let ime_ = ime.clone();
+            window.connect_focus_out_event(move |_, _| {
+              ime_.focus_out();
+              glib::Propagation::Proceed
+            });
+
+            let tx_clone = event_tx.clone();
             ime.connect_commit(move |_, s| {
               if let Err(e) = tx_clone.send(Event::WindowEvent {
                 window_id: RootWindowId(id),
@@ -844,10 +1247,33 @@ impl<T: 'static> EventLoop<T> {
               }
             });
 
+            let tx_clone = event_tx.clone();
+            ime.connect_preedit_changed(move |ime| {
+              let (text, _, cursor) = ime.preedit_string();
+              // gtk gives us the cursor position in characters, convert it to bytes.
+              let cursor = text
+                .char_indices()
+                .nth(cursor as usize)
+                .map(|(i, _)| i)
+                .unwrap_or(text.len());
+              let cursor_range = if text.is_empty() {
+                None
+              } else {
+                Some((cursor, cursor))
+              };
+              if let Err(e) = tx_clone.send(Event::WindowEvent {
+                window_id: RootWindowId(id),
+                event: WindowEvent::ImePreedit(text.to_string(), cursor_range),
+              }) {
+                log::warn!("Failed to send IME preedit event to event channel: {}", e);
+              }
+            });
+
             let handler = keyboard_handler.clone();
+            let ime_ = ime.clone();
             window.connect_key_press_event(move |_, event_key| {
               handler(event_key.to_owned(), ElementState::Pressed);
-              ime.filter_keypress(event_key);
+              ime_.filter_keypress(event_key);
 
               glib::Propagation::Proceed
             });
@@ -855,9 +1281,118 @@ impl<T: 'static> EventLoop<T> {
             let handler = keyboard_handler.clone();
             window.connect_key_release_event(move |_, event_key| {
               handler(event_key.to_owned(), ElementState::Released);
+              ime.filter_keypress(event_key);
+
               glib::Propagation::Proceed
             });
 
+            // Receive file drag and drop events of the window.
+            let uri_list = gdk::Atom::intern("text/uri-list");
+            window.drag_dest_set(
+              gtk::DestDefaults::MOTION,
+              &[gtk::TargetEntry::new(
+                "text/uri-list",
+                gtk::TargetFlags::OTHER_APP,
+                0,
+              )],
+              gdk::DragAction::COPY,
+            );
+
+            let drag_state = Rc::new(RefCell::new(DragState::default()));
+            let drag_state_ = drag_state.clone();
+            let target = uri_list;
+            window.connect_drag_motion(move |window, context, _, _, time| {
+              let mut state = drag_state_.borrow_mut();
+              state.left = false;
+              // Paths are only available through a data request, so we ask for them once
+              // per drag to be able to emit `HoveredFile`.
+              if !state.requested {
+                state.requested = true;
+                window.drag_get_data(context, &target, time);
+              }
+              false
+            });
+
+            let tx_clone = event_tx.clone();
+            let drag_state_ = drag_state.clone();
+            window.connect_drag_leave(move |_, _, _| {
+              // `drag-leave` is also emitted right before `drag-drop`, so the drag only left the
+              // window or was cancelled if no drop started once the leave is handled.
+              drag_state_.borrow_mut().left = true;
+              let drag_state = drag_state_.clone();
+              let tx_clone = tx_clone.clone();
+              glib::idle_add_local_once(move || {
+                let mut state = drag_state.borrow_mut();
+                if !state.left {
+                  return;
+                }
+                let hovered = state.paths.is_some();
+                *state = DragState::default();
+                if hovered {
+                  if let Err(e) = tx_clone.send(Event::WindowEvent {
+                    window_id: RootWindowId(id),
+                    event: WindowEvent::HoveredFileCancelled,
+                  }) {
+                    log::warn!(
+                      "Failed to send hovered file cancelled event to event channel: {}",
+                      e
+                    );
+                  }
+                }
+              });
+            });
+
+            let tx_clone = event_tx.clone();
+            let drag_state_ = drag_state.clone();
+            let target = uri_list;
+            window.connect_drag_drop(move |window, context, _, _, time| {
+              let mut state = drag_state_.borrow_mut();
+              state.left = false;
+              state.dropping = true;
+              match state.paths.take() {
+                Some(paths) => {
+                  send_dropped_files(&tx_clone, id, paths);
+                  context.drag_finish(true, false, time);
+                  *state = DragState::default();
+                }
+                None if !state.requested => {
+                  state.requested = true;
+                  window.drag_get_data(context, &target, time);
+                }
+                // The data requested on `drag-motion` is still on its way and will be
+                // handled as a drop once received.
+                None => (),
+              }
+              true
+            });
+
+            let tx_clone = event_tx.clone();
+            window.connect_drag_data_received(move |_, context, _, _, data, _, time| {
+              let paths: Vec<PathBuf> = data
+                .uris()
+                .iter()
+                .filter_map(|uri| glib::filename_from_uri(uri).ok())
+                .map(|(path, _)| path)
+                .collect();
+
+              let mut state = drag_state.borrow_mut();
+              if state.dropping {
+                send_dropped_files(&tx_clone, id, paths);
+                context.drag_finish(true, false, time);
+                *state = DragState::default();
+              } else {
+                for path in &paths {
+                  if let Err(e) = tx_clone.send(Event::WindowEvent {
+                    window_id: RootWindowId(id),
+                    event: WindowEvent::HoveredFile(path.clone()),
+                  }) {
+                    log::warn!("Failed to send hovered file event to event channel: {}", e);
+                  }
+                }
+                state.paths = Some(paths);
+              }
+            });
+
             let tx_clone = event_tx.clone();
             window.connect_window_state_event(move |window, event| {
               let state = event.changed_mask();
@@ -960,6 +1495,7 @@ impl<T: 'static> EventLoop<T> {
       user_event_tx,
       events: event_rx,
       draws: draw_rx,
+      scale_factor_changes: scale_factor_rx,
       run_device_thread,
     };
 
@@ -1023,6 +1559,7 @@ impl<T: 'static> EventLoop<T> {
         let window_target = &self.window_target;
         let events = &self.events;
         let draws = &self.draws;
+        let scale_factor_changes = &self.scale_factor_changes;
 
         window_target.p.app.activate();
 
@@ -1036,7 +1573,7 @@ impl<T: 'static> EventLoop<T> {
                 break code;
               }
               ControlFlow::Wait => {
-                if !events.is_empty() {
+                if !events.is_empty() || !scale_factor_changes.is_empty() {
                   callback(
                     Event::NewEvents(StartCause::WaitCancelled {
                       start: Instant::now(),
@@ -1062,7 +1599,7 @@ impl<T: 'static> EventLoop<T> {
                     &mut control_flow,
                   );
                   state = EventState::EventQueue;
-                } else if !events.is_empty() {
+                } else if !events.is_empty() || !scale_factor_changes.is_empty() {
                   callback(
                     Event::NewEvents(StartCause::WaitCancelled {
                       start,
@@ -1090,16 +1627,41 @@ impl<T: 'static> EventLoop<T> {
                 callback(Event::LoopDestroyed, window_target, &mut control_flow);
                 break (code);
               }
-              _ => match events.try_recv() {
-                Ok(event) => match event {
-                  Event::LoopDestroyed => control_flow = ControlFlow::ExitWithCode(1),
-                  _ => callback(event, window_target, &mut control_flow),
-                },
-                Err(_) => {
-                  callback(Event::MainEventsCleared, window_target, &mut control_flow);
-                  state = EventState::DrawQueue;
+              _ => {
+                if let Ok((id, scale_factor)) = scale_factor_changes.try_recv() {
+                  if let Some(window) = window_target.p.app.window_by_id(id.0) {
+                    let (w, h) = window.size();
+                    let size: PhysicalSize<u32> = LogicalSize::new(w, h).to_physical(scale_factor);
+                    let mut new_inner_size = size;
+                    callback(
+                      Event::WindowEvent {
+                        window_id: RootWindowId(id),
+                        event: WindowEvent::ScaleFactorChanged {
+                          scale_factor,
+                          new_inner_size: &mut new_inner_size,
+                        },
+                      },
+                      window_target,
+                      &mut control_flow,
+                    );
+                    if new_inner_size != size {
+                      let (w, h) = new_inner_size.to_logical::<i32>(scale_factor).into();
+                      window.resize(w, h);
+                    }
+                  }
+                } else {
+                  match events.try_recv() {
+                    Ok(event) => match event {
+                      Event::LoopDestroyed => control_flow = ControlFlow::ExitWithCode(1),
+                      _ => callback(event, window_target, &mut control_flow),
+                    },
+                    Err(_) => {
+                      callback(Event::MainEventsCleared, window_target, &mut control_flow);
+                      state = EventState::DrawQueue;
+                    }
+                  }
                 }
-              },
+              }
             },
             EventState::DrawQueue => match control_flow {
               ControlFlow::ExitWithCode(code) => {
@@ -1107,7 +1669,7 @@ impl<T: 'static> EventLoop<T> {
                 break code;
               }
               _ => {
-                if let Ok(id) = draws.try_recv() {
+                for id in drain_redraws(draws) {
                   callback(
                     Event::RedrawRequested(RootWindowId(id)),
                     window_target,
@@ -1124,6 +1686,11 @@ impl<T: 'static> EventLoop<T> {
         if let Some(run_device_thread) = run_device_thread {
           run_device_thread.store(false, Ordering::Relaxed);
         }
+        let exclusive_modes = &window_target.p.exclusive_modes;
+        let ids = exclusive_modes.borrow().keys().copied().collect::<Vec<_>>();
+        for id in ids {
+          restore_video_mode(exclusive_modes, id);
+        }
         exit_code
       })
       .unwrap_or(1)
@@ -1179,6 +1746,124 @@ impl<T: 'static> EventLoopProxy<T> {
 
     Ok(())
   }
+
+  /// Sends a window or device event to the `EventLoop`, as if it came from the system.
+  pub fn send_nonuser_event(
+    &self,
+    event: Event<'static, T>,
+  ) -> Result<(), EventLoopClosed<Event<'static, T>>> {
+    self
+      .user_event_tx
+      .send(event)
+      .map_err(|SendError(event)| EventLoopClosed(event))?;
+
+    let context = MainContext::default();
+    context.wakeup();
+
+    Ok(())
+  }
+}
+
+/// State of a file drag and drop operation over a window.
+#[derive(Default)]
+struct DragState {
+  /// Paths of the hovered files, once received.
+  paths: Option<Vec<PathBuf>>,
+  /// Whether the drag data was already requested.
+  requested: bool,
+  /// Whether the files were dropped on the window.
+  dropping: bool,
+  /// Whether the drag left the window, unless a drop follows.
+  left: bool,
+}
+
+fn send_dropped_files<T>(
+  tx: &crossbeam_channel::Sender<Event<'static, T>>,
+  id: WindowId,
+  paths: Vec<PathBuf>,
+) {
+  for path in paths {
+    if let Err(e) = tx.send(Event::WindowEvent {
+      window_id: RootWindowId(id),
+      event: WindowEvent::DroppedFile(path),
+    }) {
+      log::warn!("Failed to send dropped file event to event channel: {}", e);
+    }
+  }
+}
+
+/// Returns the URLs and the existing files among the command line arguments.
+fn launch_urls() -> Vec<url::Url> {
+  env::args_os()
+    .skip(1)
+    .filter_map(|arg| match arg.to_str().map(url::Url::parse) {
+      Some(Ok(url)) => Some(url),
+      _ => fs::canonicalize(arg)
+        .ok()
+        .and_then(|path| url::Url::from_file_path(path).ok()),
+    })
+    .collect()
+}
+
+/// Returns the position of `device` relative to the client area of `window`.
+///
+/// The positions of crossing and motion events are relative to the child window receiving them,
+/// so they're queried on the toplevel window instead.
+fn client_position(window: &gtk::Window, device: &gdk::Device) -> Option<PhysicalPosition<f64>> {
+  let (_, x, y, _) = window.window()?.device_position_double(device);
+  Some(LogicalPosition::new(x, y).to_physical(window.scale_factor() as f64))
+}
+
+/// Restores the video mode changed by window `id` in exclusive fullscreen, if any.
+fn restore_video_mode(exclusive_modes: &ExclusiveModes, id: WindowId) {
+  let Some((crtc, mode)) = exclusive_modes.borrow_mut().remove(&id) else {
+    return;
+  };
+  if let Some(xconn) = x11::xconnection() {
+    if let Err(e) = xconn.set_crtc_mode(crtc, mode) {
+      log::warn!("Failed to restore the video mode: {}", e);
+    }
+  }
+}
+
+/// Takes all the pending redraws, keeping the first request of each window in order.
+fn drain_redraws(draws: &crossbeam_channel::Receiver<WindowId>) -> Vec<WindowId> {
+  let mut redraws = Vec::new();
+  for id in draws.try_iter() {
+    if !redraws.contains(&id) {
+      redraws.push(id);
+    }
+  }
+  redraws
+}
+
+fn connect_monitor_changed<T>(
+  monitor: &gdk::Monitor,
+  tx: &crossbeam_channel::Sender<Event<'static, T>>,
+) {
+  for property in ["geometry", "scale-factor", "workarea"] {
+    let tx = tx.clone();
+    monitor.connect_notify_local(Some(property), move |monitor, _| {
+      send_monitor_event(&tx, monitor, MonitorEvent::Changed);
+    });
+  }
+}
+
+fn send_monitor_event<T>(
+  tx: &crossbeam_channel::Sender<Event<'static, T>>,
+  monitor: &gdk::Monitor,
+  event: MonitorEvent,
+) {
+  if let Err(e) = tx.send(Event::MonitorEvent {
+    monitor: RootMonitorHandle {
+      inner: MonitorHandle {
+        monitor: monitor.clone(),
+      },
+    },
+    event,
+  }) {
+    log::warn!("Failed to send monitor event to event channel: {}", e);
+  }
 }
 
 fn assert_is_main_thread(suggested_method: &str) {
@@ -1278,3 +1963,74 @@ impl ResizeDirection {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn drain_redraws_of_multiple_windows() {
+    let (draw_tx, draws) = crossbeam_channel::unbounded();
+    for id in [2, 1, 2, 3, 1] {
+      draw_tx.send(WindowId(id)).unwrap();
+    }
+
+    // Every window is redrawn once per iteration, in the order of its first request.
+    assert_eq!(
+      drain_redraws(&draws),
+      vec![WindowId(2), WindowId(1), WindowId(3)]
+    );
+    assert!(drain_redraws(&draws).is_empty());
+
+    // Requests made after draining are delivered on the next iteration.
+    draw_tx.send(WindowId(3)).unwrap();
+    assert_eq!(drain_redraws(&draws), vec![WindowId(3)]);
+  }
+
+  // Run with `cargo test -- --ignored --test-threads 1` under a display server.
+  #[test]
+  #[ignore = "needs a display server, and gtk initialized on the test thread"]
+  fn run_return_redraws_multiple_windows() {
+    let attributes = PlatformSpecificEventLoopAttributes {
+      any_thread: true,
+      ..Default::default()
+    };
+    let mut event_loop = EventLoop::<()>::try_new(&attributes).unwrap();
+    let windows = (0..3)
+      .map(|_| {
+        crate::window::WindowBuilder::new()
+          .with_visible(false)
+          .build(event_loop.window_target())
+          .unwrap()
+      })
+      .collect::<Vec<_>>();
+
+    // The windows redrawn in each iteration, ended by `RedrawEventsCleared`.
+    let mut iterations = Vec::new();
+    let mut redraws = Vec::new();
+    event_loop.run_return(|event, _, control_flow| {
+      *control_flow = ControlFlow::Poll;
+      match event {
+        Event::MainEventsCleared if iterations.len() < 2 => {
+          for i in [2, 0, 2, 1, 0] {
+            windows[i].request_redraw();
+          }
+        }
+        Event::RedrawRequested(id) => redraws.push(id),
+        Event::RedrawEventsCleared => {
+          iterations.push(std::mem::take(&mut redraws));
+          if iterations.len() == 3 {
+            *control_flow = ControlFlow::ExitWithCode(0);
+          }
+        }
+        _ => {}
+      }
+    });
+
+    // Every window is redrawn once per iteration, before `RedrawEventsCleared`, in the order of
+    // its first request.
+    let expected = [2, 0, 1].map(|i| windows[i].id()).to_vec();
+    assert_eq!(iterations, vec![expected.clone(), expected, vec![]]);
+    assert!(redraws.is_empty());
+  }
+}
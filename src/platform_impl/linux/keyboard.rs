@@ -124,45 +124,78 @@ pub(crate) fn raw_key_to_location(raw: RawKey) -> KeyLocation {
   }
 }
 
-const MODIFIER_MAP: &[(Key<'static>, ModifiersState)] = &[
-  (Key::Shift, ModifiersState::SHIFT),
-  (Key::Alt, ModifiersState::ALT),
-  (Key::Control, ModifiersState::CONTROL),
-  (Key::Super, ModifiersState::SUPER),
-];
-
 // we use the EventKey to extract the modifier mainly because
 // we need to have the modifier before the second key is entered to follow
 // other os' logic -- this way we can emit the new `ModifiersState` before
 // we receive the next key, if needed the developer can update his local state.
-pub(crate) fn get_modifiers(key: EventKey) -> ModifiersState {
-  // a keycode (scancode in Windows) is a code that refers to a physical keyboard key.
-  let scancode = key.hardware_keycode();
-  // a keyval (keysym in X) is a "logical" key name, such as GDK_Enter, GDK_a, GDK_space, etc.
-  let keyval = key.keyval();
-  // unicode value
-  let unicode = keyval.to_unicode();
-  // translate to tao::keyboard::Key
-  let key_from_code = raw_key_to_key(keyval).unwrap_or_else(|| {
-    if let Some(key) = unicode {
-      if key >= ' ' && key != '\x7f' {
-        Key::Character(insert_or_get_key_str(key.to_string()))
-      } else {
-        Key::Unidentified(NativeKeyCode::Gtk(scancode))
-      }
-    } else {
-      Key::Unidentified(NativeKeyCode::Gtk(scancode))
+#[allow(non_upper_case_globals)]
+pub(crate) fn get_modifier(key: &EventKey) -> Option<ModifiersState> {
+  match key.keyval() {
+    Shift_L => Some(ModifiersState::LSHIFT),
+    Shift_R => Some(ModifiersState::RSHIFT),
+    Control_L => Some(ModifiersState::LCONTROL),
+    Control_R => Some(ModifiersState::RCONTROL),
+    Alt_L => Some(ModifiersState::LALT),
+    Alt_R => Some(ModifiersState::RALT),
+    Super_L => Some(ModifiersState::LSUPER),
+    Super_R => Some(ModifiersState::RSUPER),
+    _ => None,
+  }
+}
+
+/// The combined modifiers with their sides and the gdk masks they're reported with.
+const SIDED_MODIFIERS: [(ModifiersState, ModifiersState, gdk::ModifierType); 4] = [
+  (
+    ModifiersState::SHIFT,
+    ModifiersState::LSHIFT.union(ModifiersState::RSHIFT),
+    gdk::ModifierType::SHIFT_MASK,
+  ),
+  (
+    ModifiersState::CONTROL,
+    ModifiersState::LCONTROL.union(ModifiersState::RCONTROL),
+    gdk::ModifierType::CONTROL_MASK,
+  ),
+  (
+    ModifiersState::ALT,
+    ModifiersState::LALT.union(ModifiersState::RALT),
+    gdk::ModifierType::MOD1_MASK,
+  ),
+  (
+    ModifiersState::SUPER,
+    ModifiersState::LSUPER.union(ModifiersState::RSUPER),
+    gdk::ModifierType::SUPER_MASK.union(gdk::ModifierType::MOD4_MASK),
+  ),
+];
+
+/// Returns the modifiers held down when `key` was sent, which includes the ones pressed while
+/// another window was focused. Only the sides of `tracked` whose modifier is still held down are
+/// kept.
+pub(crate) fn seed_modifiers(key: &EventKey, tracked: ModifiersState) -> ModifiersState {
+  let state = key.state();
+  let mut modifiers = ModifiersState::empty();
+  for (combined, sides, mask) in SIDED_MODIFIERS {
+    if state.intersects(mask) {
+      modifiers |= combined | (tracked & sides);
     }
-  });
-  // start with empty state
-  let mut result = ModifiersState::empty();
-  // loop trough our modifier map
-  for (gdk_mod, modifier) in MODIFIER_MAP {
-    if key_from_code == *gdk_mod {
-      result |= *modifier;
+  }
+  modifiers
+}
+
+/// Applies the press or release of a sided `modifier` to `modifiers`, keeping the combined flag
+/// set while the other side is pressed.
+pub(crate) fn update_modifiers(
+  mut modifiers: ModifiersState,
+  modifier: ModifiersState,
+  state: ElementState,
+) -> ModifiersState {
+  let pressed = state == ElementState::Pressed;
+  for (combined, sides, _) in SIDED_MODIFIERS {
+    if sides.contains(modifier) {
+      modifiers.set(modifier, pressed);
+      modifiers.set(combined, pressed || modifiers.intersects(sides));
     }
   }
-  result
+  modifiers
 }
 
 pub(crate) fn make_key_event(
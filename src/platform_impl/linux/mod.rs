@@ -30,7 +30,19 @@ pub use icon::PlatformIcon;
 pub use monitor::{MonitorHandle, VideoMode};
 pub use window::{Window, WindowId};
 
-use crate::{event::DeviceId as RootDeviceId, keyboard::Key};
+use std::{
+  os::raw::c_int,
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+use gtk::gdk::{self, prelude::*};
+
+use crate::{
+  event::DeviceId as RootDeviceId,
+  keyboard::Key,
+  platform::unix::OsErrorKind,
+  platform::unix::{Anchor, KeyboardInteractivity, Layer, WindowType},
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEventExtra {
@@ -61,6 +73,22 @@ pub struct PlatformSpecificWindowBuilderAttributes {
   pub rgba_visual: bool,
   pub cursor_moved: bool,
   pub default_vbox: bool,
+  pub window_type: Option<WindowType>,
+  pub skip_pager: bool,
+  pub urgent: bool,
+  pub override_redirect: bool,
+  pub layer: Option<Layer>,
+  pub layer_shell: LayerShellAttributes,
+}
+
+/// Layer-shell options, only used when [`PlatformSpecificWindowBuilderAttributes::layer`] is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LayerShellAttributes {
+  pub anchor: Anchor,
+  /// Top, right, bottom and left margins.
+  pub margins: [i32; 4],
+  pub exclusive_zone: i32,
+  pub keyboard_interactivity: KeyboardInteractivity,
 }
 
 impl Default for PlatformSpecificWindowBuilderAttributes {
@@ -74,6 +102,12 @@ impl Default for PlatformSpecificWindowBuilderAttributes {
       rgba_visual: false,
       cursor_moved: true,
       default_vbox: true,
+      window_type: None,
+      skip_pager: false,
+      urgent: false,
+      override_redirect: false,
+      layer: None,
+      layer_shell: Default::default(),
     }
   }
 }
@@ -82,14 +116,33 @@ unsafe impl Send for PlatformSpecificWindowBuilderAttributes {}
 unsafe impl Sync for PlatformSpecificWindowBuilderAttributes {}
 
 #[derive(Debug, Clone)]
-pub struct OsError;
+pub struct OsError {
+  pub(crate) kind: OsErrorKind,
+  pub(crate) message: String,
+}
+
+impl OsError {
+  pub(crate) fn new(kind: OsErrorKind, message: impl Into<String>) -> Self {
+    Self {
+      kind,
+      message: message.into(),
+    }
+  }
+}
+
+impl From<x11::xdisplay::XError> for OsError {
+  fn from(error: x11::xdisplay::XError) -> Self {
+    Self::new(OsErrorKind::X(error.error_code), error.to_string())
+  }
+}
 
 impl std::fmt::Display for OsError {
-  fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-    Ok(())
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+    f.write_str(&self.message)
   }
 }
 
+/// The XInput2 id of a device on X11, or the address of its `gdk::Device` on Wayland.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DeviceId(usize);
 
@@ -97,7 +150,57 @@ impl DeviceId {
   pub unsafe fn dummy() -> Self {
     Self(0)
   }
+
+  pub(crate) fn from_gdk(device: &gdk::Device) -> Self {
+    if device.display().backend().is_x11() {
+      Self::from_xinput(unsafe { gdk_x11_sys::gdk_x11_device_get_id(device.as_ptr() as *mut _) })
+    } else {
+      // Device pointers can be reused once a device is removed, so ids are handed out once per
+      // device instead.
+      static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+      unsafe {
+        if let Some(id) = device.data::<usize>("device-id") {
+          return Self(*id.as_ref());
+        }
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        device.set_data("device-id", id);
+        Self(id)
+      }
+    }
+  }
+
+  pub(crate) fn from_xinput(id: c_int) -> Self {
+    Self(id as usize)
+  }
+
+  /// Returns the gdk device with this id, if it's still connected.
+  pub fn gdk_device(&self) -> Option<gdk::Device> {
+    // gdk panics when it's queried from another thread or before it's initialized.
+    if !gtk::is_initialized_main_thread() {
+      return None;
+    }
+    let display = gdk::Display::default()?;
+    display
+      .list_seats()
+      .iter()
+      .flat_map(|seat| {
+        let mut devices = seat.slaves(gdk::SeatCapabilities::ALL);
+        devices.extend(seat.pointer());
+        devices.extend(seat.keyboard());
+        devices
+      })
+      .find(|device| Self::from_gdk(device) == *self)
+  }
 }
 
-// FIXME: currently we use a dummy device id, find if we can get device id from gtk
+/// Used when the device of an event is unknown.
 pub(crate) const DEVICE_ID: RootDeviceId = RootDeviceId(DeviceId(0));
+
+/// Returns the id of the physical device that emitted `event`.
+pub(crate) fn device_id(event: &gdk::Event) -> RootDeviceId {
+  event
+    .source_device()
+    .or_else(|| event.device())
+    .map(|device| RootDeviceId(DeviceId::from_gdk(&device)))
+    .unwrap_or(DEVICE_ID)
+}
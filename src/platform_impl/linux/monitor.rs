@@ -2,14 +2,20 @@
 // Copyright 2021-2023 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
-use gtk::gdk::{self, prelude::MonitorExt, Display};
+use gtk::gdk::{
+  self,
+  prelude::{DisplayExtManual, MonitorExt},
+  Display,
+};
 
 use crate::{
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
   monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+use super::x11::{self, ffi};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MonitorHandle {
   pub(crate) monitor: gdk::Monitor,
 }
@@ -50,9 +56,71 @@ impl MonitorHandle {
     self.monitor.scale_factor() as f64
   }
 
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    // GDK reports 0 when the refresh rate is unknown.
+    let refresh_rate = self.monitor.refresh_rate();
+    (refresh_rate > 0).then_some(refresh_rate as u32)
+  }
+
+  #[inline]
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    let (width, height) = (self.monitor.width_mm(), self.monitor.height_mm());
+    (width > 0 && height > 0).then_some((width as u32, height as u32))
+  }
+
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let rect = self.monitor.workarea();
+    let scale_factor = self.scale_factor();
+    (
+      LogicalPosition::new(rect.x(), rect.y()).to_physical(scale_factor),
+      LogicalSize::new(rect.width() as u32, rect.height() as u32).to_physical(scale_factor),
+    )
+  }
+
+  #[inline]
+  pub fn stable_id(&self) -> Option<String> {
+    // GDK doesn't expose the EDID. On X11 the model is the connector name.
+    let parts = [self.monitor.manufacturer(), self.monitor.model()]
+      .into_iter()
+      .flatten()
+      .map(|s| s.to_string())
+      .collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join("-"))
+  }
+
   #[inline]
   pub fn video_modes(&self) -> Box<dyn Iterator<Item = RootVideoMode>> {
-    Box::new(Vec::new().into_iter())
+    // Only X11 allows clients to change the video mode.
+    let is_x11 = self
+      .monitor
+      .display()
+      .map(|d| d.backend().is_x11())
+      .unwrap_or(false);
+    if !is_x11 {
+      return Box::new(Vec::new().into_iter());
+    }
+
+    let Some(xconn) = x11::xconnection() else {
+      return Box::new(Vec::new().into_iter());
+    };
+    let bit_depth = xconn.default_depth();
+    let modes = xconn
+      .crtc_at(self.position().into())
+      .map(|crtc| crtc.modes)
+      .unwrap_or_default();
+
+    let monitor = self.clone();
+    Box::new(modes.into_iter().map(move |mode| RootVideoMode {
+      video_mode: VideoMode {
+        size: (mode.width, mode.height),
+        bit_depth,
+        refresh_rate: mode.refresh_rate,
+        native_mode: mode.id,
+        monitor: monitor.clone(),
+      },
+    }))
   }
 }
 
@@ -60,27 +128,36 @@ unsafe impl Send for MonitorHandle {}
 unsafe impl Sync for MonitorHandle {}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct VideoMode;
+pub struct VideoMode {
+  pub(crate) size: (u32, u32),
+  pub(crate) bit_depth: u16,
+  pub(crate) refresh_rate: u16,
+  /// XRandR mode id.
+  pub(crate) native_mode: ffi::RRMode,
+  pub(crate) monitor: MonitorHandle,
+}
 
 impl VideoMode {
   #[inline]
   pub fn size(&self) -> PhysicalSize<u32> {
-    panic!("VideoMode is unsupported on Linux.")
+    self.size.into()
   }
 
   #[inline]
   pub fn bit_depth(&self) -> u16 {
-    panic!("VideoMode is unsupported on Linux.")
+    self.bit_depth
   }
 
   #[inline]
   pub fn refresh_rate(&self) -> u16 {
-    panic!("VideoMode is unsupported on Linux.")
+    self.refresh_rate
   }
 
   #[inline]
   pub fn monitor(&self) -> RootMonitorHandle {
-    panic!("VideoMode is unsupported on Linux.")
+    RootMonitorHandle {
+      inner: self.monitor.clone(),
+    }
   }
 }
 
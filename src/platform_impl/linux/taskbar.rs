@@ -1,88 +1,75 @@
-use std::ffi::{c_char, CString};
+use std::{
+  collections::{hash_map::DefaultHasher, HashMap},
+  hash::{Hash, Hasher},
+};
 
-use dlopen2::wrapper::{Container, WrapperApi};
+use gtk::{
+  gio::{self, Cancellable, DBusConnection},
+  glib::{ToVariant, Variant},
+};
 
 use crate::window::{ProgressBarState, ProgressState};
 
-#[derive(WrapperApi)]
-struct UnityLib {
-  unity_launcher_entry_get_for_desktop_id: unsafe extern "C" fn(id: *const c_char) -> *const isize,
-  unity_inspector_get_default: unsafe extern "C" fn() -> *const isize,
-  unity_inspector_get_unity_running: unsafe extern "C" fn(inspector: *const isize) -> i32,
-  unity_launcher_entry_set_progress: unsafe extern "C" fn(entry: *const isize, value: f64) -> i32,
-  unity_launcher_entry_set_progress_visible:
-    unsafe extern "C" fn(entry: *const isize, value: i32) -> i32,
-  unity_launcher_entry_set_count: unsafe extern "C" fn(entry: *const isize, value: i64) -> i32,
-  unity_launcher_entry_set_count_visible:
-    unsafe extern "C" fn(entry: *const isize, value: bool) -> bool,
-}
+const LAUNCHER_ENTRY_INTERFACE: &str = "com.canonical.Unity.LauncherEntry";
 
+/// Updates the launcher entry of the application through the `com.canonical.Unity.LauncherEntry`
+/// D-Bus API, supported by Unity, KDE Plasma and the GNOME docks among others.
 pub struct TaskbarIndicator {
   desktop_filename: Option<String>,
-  desktop_filename_c_str: Option<CString>,
-
-  unity_lib: Option<Container<UnityLib>>,
-  attempted_load: bool,
 
-  unity_inspector: Option<*const isize>,
-  unity_entry: Option<*const isize>,
+  connection: Option<DBusConnection>,
+  attempted_connection: bool,
 }
 
 impl TaskbarIndicator {
   pub fn new() -> Self {
     Self {
       desktop_filename: None,
-      desktop_filename_c_str: None,
 
-      unity_lib: None,
-      attempted_load: false,
-
-      unity_inspector: None,
-      unity_entry: None,
+      connection: None,
+      attempted_connection: false,
     }
   }
 
-  fn ensure_lib_load(&mut self) {
-    if self.attempted_load {
+  fn ensure_connection(&mut self) {
+    if self.attempted_connection {
       return;
     }
 
-    self.attempted_load = true;
+    self.attempted_connection = true;
 
-    self.unity_lib = unsafe {
-      Container::load("libunity.so.4")
-        .or_else(|_| Container::load("libunity.so.6"))
-        .or_else(|_| Container::load("libunity.so.9"))
-        .ok()
-    };
-
-    if let Some(unity_lib) = &self.unity_lib {
-      let handle = unsafe { unity_lib.unity_inspector_get_default() };
-      if !handle.is_null() {
-        self.unity_inspector = Some(handle);
-      }
+    match gio::bus_get_sync(gio::BusType::Session, None::<&Cancellable>) {
+      Ok(connection) => self.connection = Some(connection),
+      Err(e) => log::warn!("Failed to connect to the session bus: {}", e),
     }
   }
 
-  fn ensure_entry_load(&mut self) {
-    if let Some(unity_lib) = &self.unity_lib {
-      if let Some(id) = &self.desktop_filename_c_str {
-        let handle = unsafe { unity_lib.unity_launcher_entry_get_for_desktop_id(id.as_ptr()) };
-        if !handle.is_null() {
-          self.unity_entry = Some(handle);
-        }
-      }
-    }
-  }
+  /// Emits the `Update` signal with the changed `properties` of the launcher entry.
+  fn emit_update(&mut self, properties: HashMap<&str, Variant>) {
+    let Some(desktop_filename) = &self.desktop_filename else {
+      return;
+    };
+    let app_uri = format!("application://{}", desktop_filename);
 
-  fn is_unity_running(&self) -> bool {
-    if let Some(inspector) = self.unity_inspector {
-      if let Some(unity_lib) = &self.unity_lib {
-        return unsafe { unity_lib.unity_inspector_get_unity_running(inspector) } == 1;
-      }
-    }
+    self.ensure_connection();
+    let Some(connection) = &self.connection else {
+      return;
+    };
 
-    false
+    // The path only needs to be unique to the application.
+    let mut hasher = DefaultHasher::new();
+    app_uri.hash(&mut hasher);
+    let object_path = format!("/com/canonical/unity/launcherentry/{}", hasher.finish());
+
+    if let Err(e) = connection.emit_signal(
+      None,
+      &object_path,
+      LAUNCHER_ENTRY_INTERFACE,
+      "Update",
+      Some(&(app_uri, properties).to_variant()),
+    ) {
+      log::warn!("Failed to update the launcher entry: {}", e);
+    }
   }
 
   pub fn update(&mut self, progress: ProgressBarState) {
@@ -90,38 +77,25 @@ impl TaskbarIndicator {
       self.desktop_filename = Some(uri);
     }
 
-    self.ensure_lib_load();
-
-    if !self.is_unity_running() {
-      return;
+    let mut properties = HashMap::new();
+    if let Some(progress) = progress.progress {
+      let progress = if progress > 100 { 100 } else { progress };
+      properties.insert("progress", (progress as f64 / 100.0).to_variant());
     }
 
-    if let Some(uri) = &self.desktop_filename {
-      self.desktop_filename_c_str = Some(CString::new(uri.as_str()).unwrap_or_default());
+    if let Some(state) = progress.state {
+      // The launcher entry can't show an indeterminate progress, and only has the urgent hint to
+      // tell errors apart.
+      let (is_visible, is_urgent) = match state {
+        ProgressState::None | ProgressState::Indeterminate => (false, false),
+        ProgressState::Normal | ProgressState::Paused => (true, false),
+        ProgressState::Error => (true, true),
+      };
+      properties.insert("progress-visible", is_visible.to_variant());
+      properties.insert("urgent", is_urgent.to_variant());
     }
 
-    if self.unity_entry.is_none() {
-      self.ensure_entry_load();
-    }
-    if let Some(unity_lib) = &self.unity_lib {
-      if let Some(unity_entry) = &self.unity_entry {
-        if let Some(progress) = progress.progress {
-          let progress = if progress > 100 { 100 } else { progress };
-          let progress = progress as f64 / 100.0;
-          unsafe { (unity_lib.unity_launcher_entry_set_progress)(*unity_entry, progress) };
-        }
-
-        if let Some(state) = progress.state {
-          let is_visible = !matches!(state, ProgressState::None);
-          unsafe {
-            (unity_lib.unity_launcher_entry_set_progress_visible)(
-              *unity_entry,
-              if is_visible { 1 } else { 0 },
-            )
-          };
-        }
-      }
-    }
+    self.emit_update(properties);
   }
 
   pub fn update_count(&mut self, count: Option<i64>, desktop_filename: Option<String>) {
@@ -129,33 +103,10 @@ impl TaskbarIndicator {
       self.desktop_filename = Some(uri);
     }
 
-    self.ensure_lib_load();
-
-    if !self.is_unity_running() {
-      return;
-    }
-
-    if let Some(uri) = &self.desktop_filename {
-      self.desktop_filename_c_str = Some(CString::new(uri.as_str()).unwrap_or_default());
-    }
-
-    if self.unity_entry.is_none() {
-      self.ensure_entry_load();
-    }
+    let mut properties = HashMap::new();
+    properties.insert("count", count.unwrap_or(0).to_variant());
+    properties.insert("count-visible", count.is_some().to_variant());
 
-    if let Some(unity_lib) = &self.unity_lib {
-      if let Some(unity_entry) = &self.unity_entry {
-        // Sets count
-        if let Some(count) = count {
-          unsafe { (unity_lib.unity_launcher_entry_set_count)(*unity_entry, count) };
-          unsafe { (unity_lib.unity_launcher_entry_set_count_visible)(*unity_entry, true) };
-        }
-        // removes the count
-        else {
-          unsafe { (unity_lib.unity_launcher_entry_set_count)(*unity_entry, 0) };
-          unsafe { (unity_lib.unity_launcher_entry_set_count_visible)(*unity_entry, false) };
-        }
-      }
-    }
+    self.emit_update(properties);
   }
 }
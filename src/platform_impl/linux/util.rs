@@ -1,40 +1,43 @@
 use crate::{
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
   error::ExternalError,
+  platform::unix::{Anchor, KeyboardInteractivity, Layer, OsErrorKind, WindowType},
+  platform_impl::{wayland::header::WlHeader, LayerShellAttributes, OsError},
   window::WindowSizeConstraints,
 };
 use gtk::{
   gdk::{
     self,
-    prelude::{DeviceExt, SeatExt},
-    Display,
+    prelude::{DeviceExt, MonitorExt, SeatExt},
+    Display, WMFunction,
   },
-  glib::{self},
-  traits::{GtkWindowExt, WidgetExt},
+  glib::{self, prelude::*},
+  traits::{GtkWindowExt, HeaderBarExt, WidgetExt},
 };
 use std::{cell::RefCell, rc::Rc};
 
+/// Returns the position of the cursor on the screen. Only works on X11 since Wayland doesn't
+/// expose global positions, see [`super::event_loop::CursorPosition`] instead.
 #[inline]
-pub fn cursor_position(is_wayland: bool) -> Result<PhysicalPosition<f64>, ExternalError> {
-  if is_wayland {
-    Ok((0, 0).into())
-  } else {
-    Display::default()
-      .map(|d| {
-        (
-          d.default_seat().and_then(|s| s.pointer()),
-          d.default_group(),
-        )
-      })
-      .map(|(p, g)| {
-        p.map(|p| {
-          let (_, x, y) = p.position_double();
-          LogicalPosition::new(x, y).to_physical(g.scale_factor() as _)
-        })
-      })
-      .map(|p| p.ok_or(ExternalError::Os(os_error!(super::OsError))))
-      .ok_or(ExternalError::Os(os_error!(super::OsError)))?
-  }
+pub fn cursor_position() -> Result<PhysicalPosition<f64>, ExternalError> {
+  let display = Display::default().ok_or_else(|| {
+    ExternalError::Os(os_error!(OsError::new(
+      OsErrorKind::NoDisplay,
+      "No default display found"
+    )))
+  })?;
+  let pointer = display
+    .default_seat()
+    .and_then(|s| s.pointer())
+    .ok_or_else(|| {
+      ExternalError::Os(os_error!(OsError::new(
+        OsErrorKind::Other,
+        "No pointer device found"
+      )))
+    })?;
+
+  let (_, x, y) = pointer.position_double();
+  Ok(LogicalPosition::new(x, y).to_physical(display.default_group().scale_factor() as _))
 }
 
 pub fn set_size_constraints<W: GtkWindowExt + WidgetExt>(
@@ -74,6 +77,228 @@ pub fn set_size_constraints<W: GtkWindowExt + WidgetExt>(
   )
 }
 
+/// Sets whether the window can be minimized or maximized.
+///
+/// GTK has no such properties, so they are stored on the window and applied by [`update_window_buttons`].
+pub fn set_window_buttons<W: IsA<gtk::Window>>(
+  window: &W,
+  minimizable: Option<bool>,
+  maximizable: Option<bool>,
+) {
+  unsafe {
+    if let Some(minimizable) = minimizable {
+      window.set_data("minimizable", minimizable);
+    }
+    if let Some(maximizable) = maximizable {
+      window.set_data("maximizable", maximizable);
+    }
+  }
+  update_window_buttons(window);
+}
+
+fn window_button_flag(window: &gtk::Window, key: &str) -> bool {
+  unsafe { window.data::<bool>(key).map(|v| *v.as_ref()) }.unwrap_or(true)
+}
+
+/// Whether the window can be minimized, as set by [`set_window_buttons`].
+pub fn is_minimizable<W: IsA<gtk::Window>>(window: &W) -> bool {
+  window_button_flag(window.upcast_ref(), "minimizable")
+}
+
+/// Whether the window can be maximized, which also requires it to be resizable.
+pub fn is_maximizable<W: IsA<gtk::Window>>(window: &W) -> bool {
+  let window = window.upcast_ref::<gtk::Window>();
+  window_button_flag(window, "maximizable") && window.is_resizable()
+}
+
+/// Enables the minimize, maximize and close buttons matching the window capabilities, through the
+/// header bar on Wayland and the window manager hints on X11.
+pub fn update_window_buttons<W: IsA<gtk::Window>>(window: &W) {
+  let window = window.upcast_ref::<gtk::Window>();
+  let resizable = window.is_resizable();
+  let minimizable = is_minimizable(window);
+  let maximizable = is_maximizable(window);
+  let closable = window.is_deletable();
+
+  if let Some(header) = WlHeader::header_bar(window) {
+    let buttons = [
+      (minimizable, "minimize"),
+      (maximizable, "maximize"),
+      (closable, "close"),
+    ]
+    .iter()
+    .filter_map(|(enabled, button)| enabled.then_some(*button))
+    .collect::<Vec<_>>();
+    header.set_decoration_layout(Some(&format!("menu:{}", buttons.join(","))));
+  } else if let Some(gdk_window) = window.window() {
+    let mut functions = WMFunction::MOVE;
+    functions.set(WMFunction::RESIZE, resizable);
+    functions.set(WMFunction::MINIMIZE, minimizable);
+    functions.set(WMFunction::MAXIMIZE, maximizable);
+    functions.set(WMFunction::CLOSE, closable);
+    gdk_window.set_functions(functions);
+  }
+}
+
+pub fn window_type_hint(window_type: WindowType) -> gdk::WindowTypeHint {
+  match window_type {
+    WindowType::Normal => gdk::WindowTypeHint::Normal,
+    WindowType::Dialog => gdk::WindowTypeHint::Dialog,
+    WindowType::Utility => gdk::WindowTypeHint::Utility,
+    WindowType::Splash => gdk::WindowTypeHint::Splashscreen,
+    WindowType::Notification => gdk::WindowTypeHint::Notification,
+    WindowType::Tooltip => gdk::WindowTypeHint::Tooltip,
+    WindowType::PopupMenu => gdk::WindowTypeHint::PopupMenu,
+  }
+}
+
+/// Sets whether `window` bypasses the window manager. Override-redirect is only read when the
+/// window is mapped, so a visible window is mapped again.
+pub fn set_override_redirect<W: IsA<gtk::Window>>(window: &W, override_redirect: bool) {
+  let window = window.upcast_ref::<gtk::Window>();
+  let visible = window.is_visible();
+  if visible {
+    window.hide();
+  }
+  window.realize();
+  if let Some(gdk_window) = window.window() {
+    gdk_window.set_override_redirect(override_redirect);
+  }
+  if visible {
+    window.show();
+  }
+}
+
+/// Makes `window` a dock placed like a layer-shell surface, since X11 has no layer-shell. It must
+/// not be realized yet.
+pub fn setup_dock<W: IsA<gtk::Window>>(window: &W, layer: Layer, attributes: LayerShellAttributes) {
+  let window = window.upcast_ref::<gtk::Window>();
+  window.set_type_hint(gdk::WindowTypeHint::Dock);
+  window.stick();
+  match layer {
+    Layer::Top | Layer::Overlay => window.set_keep_above(true),
+    Layer::Background | Layer::Bottom => window.set_keep_below(true),
+  }
+  if attributes.keyboard_interactivity == KeyboardInteractivity::None {
+    window.set_accept_focus(false);
+  }
+  window.connect_realize(move |window| place_dock(window, attributes));
+}
+
+/// Moves the dock to its anchor and reserves its exclusive zone with struts.
+fn place_dock(window: &gtk::Window, attributes: LayerShellAttributes) {
+  let display = window.display();
+  let (Some(gdk_window), Some(monitor)) = (
+    window.window(),
+    display.primary_monitor().or_else(|| display.monitor(0)),
+  ) else {
+    return;
+  };
+
+  let area = monitor.geometry();
+  let anchor = attributes.anchor;
+  let [top, right, bottom, left] = attributes.margins;
+  let (mut width, mut height) = window.size();
+  if anchor.contains(Anchor::LEFT | Anchor::RIGHT) {
+    width = area.width() - left - right;
+  }
+  if anchor.contains(Anchor::TOP | Anchor::BOTTOM) {
+    height = area.height() - top - bottom;
+  }
+  let x = if anchor.contains(Anchor::LEFT) {
+    area.x() + left
+  } else if anchor.contains(Anchor::RIGHT) {
+    area.x() + area.width() - width - right
+  } else {
+    area.x() + (area.width() - width) / 2
+  };
+  let y = if anchor.contains(Anchor::TOP) {
+    area.y() + top
+  } else if anchor.contains(Anchor::BOTTOM) {
+    area.y() + area.height() - height - bottom
+  } else {
+    area.y() + (area.height() - height) / 2
+  };
+  window.resize(width, height);
+  window.move_(x, y);
+
+  // Struts are in physical pixels, relative to the edges of the root window.
+  let scale = monitor.scale_factor();
+  let (root_width, root_height) = (0..display.n_monitors())
+    .filter_map(|i| display.monitor(i))
+    .map(|m| m.geometry())
+    .fold((0, 0), |(w, h), g| {
+      (w.max(g.x() + g.width()), h.max(g.y() + g.height()))
+    });
+  let zone = attributes.exclusive_zone;
+  // left, right, top, bottom, then the start and end of each along its edge.
+  let mut strut = [0 as std::ffi::c_ulong; 12];
+  if zone > 0 {
+    let physical = |v: i32| (v.max(0) * scale) as std::ffi::c_ulong;
+    let (x_end, y_end) = (
+      physical(x + width).saturating_sub(1),
+      physical(y + height).saturating_sub(1),
+    );
+    match exclusive_edge(anchor) {
+      Some(Anchor::LEFT) => {
+        strut[0] = physical(area.x() + left + zone);
+        strut[4] = physical(y);
+        strut[5] = y_end;
+      }
+      Some(Anchor::RIGHT) => {
+        strut[1] = physical(root_width - (area.x() + area.width() - right - zone));
+        strut[6] = physical(y);
+        strut[7] = y_end;
+      }
+      Some(Anchor::TOP) => {
+        strut[2] = physical(area.y() + top + zone);
+        strut[8] = physical(x);
+        strut[9] = x_end;
+      }
+      Some(Anchor::BOTTOM) => {
+        strut[3] = physical(root_height - (area.y() + area.height() - bottom - zone));
+        strut[10] = physical(x);
+        strut[11] = x_end;
+      }
+      _ => {}
+    }
+  }
+
+  let cardinal = gdk::Atom::intern("CARDINAL");
+  gdk::property_change(
+    &gdk_window,
+    &gdk::Atom::intern("_NET_WM_STRUT_PARTIAL"),
+    &cardinal,
+    32,
+    gdk::PropMode::Replace,
+    gdk::ChangeData::ULongs(&strut),
+  );
+  gdk::property_change(
+    &gdk_window,
+    &gdk::Atom::intern("_NET_WM_STRUT"),
+    &cardinal,
+    32,
+    gdk::PropMode::Replace,
+    gdk::ChangeData::ULongs(&strut[..4]),
+  );
+}
+
+/// Returns the edge an exclusive zone applies to: the single anchored edge, or the edge anchored
+/// along with both perpendicular ones.
+fn exclusive_edge(anchor: Anchor) -> Option<Anchor> {
+  let horizontal = Anchor::LEFT | Anchor::RIGHT;
+  let vertical = Anchor::TOP | Anchor::BOTTOM;
+  [
+    (Anchor::TOP, horizontal),
+    (Anchor::BOTTOM, horizontal),
+    (Anchor::LEFT, vertical),
+    (Anchor::RIGHT, vertical),
+  ]
+  .into_iter()
+  .find(|(edge, perpendicular)| anchor == *edge || anchor == *edge | *perpendicular)
+  .map(|(edge, _)| edge)
+}
+
 pub struct WindowMaximizeProcess<W: GtkWindowExt + WidgetExt> {
   window: W,
   resizable: bool,
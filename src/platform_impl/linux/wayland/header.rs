@@ -17,20 +17,14 @@ impl WlHeader {
     event_box.add(&header);
 
     window.set_titlebar(Some(&event_box));
-    Self::connect_resize_window(&header, window);
   }
 
-  fn connect_resize_window(header: &HeaderBar, window: &ApplicationWindow) {
-    let header_weak = header.downgrade();
-    window.connect_resizable_notify(move |window| {
-      if let Some(header) = header_weak.upgrade() {
-        let is_resizable = window.is_resizable();
-        header.set_decoration_layout(if !is_resizable {
-          Some("menu:minimize,close")
-        } else {
-          Some("menu:minimize,maximize,close")
-        });
-      }
-    });
+  /// Returns the header bar set up for `window`, if any.
+  pub fn header_bar(window: &gtk::Window) -> Option<HeaderBar> {
+    window
+      .titlebar()
+      .and_then(|titlebar| titlebar.downcast::<EventBox>().ok())
+      .and_then(|event_box| event_box.child())
+      .and_then(|child| child.downcast::<HeaderBar>().ok())
   }
 }
@@ -0,0 +1,88 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! Layer-shell windows through `libgtk-layer-shell`, loaded at runtime.
+
+use std::{ffi::c_int, sync::OnceLock};
+
+use dlopen2::wrapper::{Container, WrapperApi};
+use gtk::{ffi::GtkWindow, glib::translate::ToGlibPtr};
+
+use crate::{
+  platform::unix::{Anchor, KeyboardInteractivity, Layer},
+  platform_impl::LayerShellAttributes,
+};
+
+#[derive(WrapperApi)]
+struct GtkLayerShell {
+  gtk_layer_is_supported: unsafe extern "C" fn() -> c_int,
+  gtk_layer_init_for_window: unsafe extern "C" fn(window: *mut GtkWindow),
+  gtk_layer_set_layer: unsafe extern "C" fn(window: *mut GtkWindow, layer: c_int),
+  gtk_layer_set_anchor: unsafe extern "C" fn(window: *mut GtkWindow, edge: c_int, anchor: c_int),
+  gtk_layer_set_margin:
+    unsafe extern "C" fn(window: *mut GtkWindow, edge: c_int, margin_size: c_int),
+  gtk_layer_set_exclusive_zone: unsafe extern "C" fn(window: *mut GtkWindow, exclusive_zone: c_int),
+  gtk_layer_set_keyboard_mode: unsafe extern "C" fn(window: *mut GtkWindow, mode: c_int),
+}
+
+// `GtkLayerShellEdge` values, in the order of `LayerShellAttributes::margins`.
+const EDGES: [(Anchor, c_int); 4] = [
+  (Anchor::TOP, 2),
+  (Anchor::RIGHT, 1),
+  (Anchor::BOTTOM, 3),
+  (Anchor::LEFT, 0),
+];
+
+/// The library is never unloaded, since it hooks into gtk.
+static LIBRARY: OnceLock<Option<Container<GtkLayerShell>>> = OnceLock::new();
+
+/// Turns `window` into a layer-shell surface. It must not be realized yet.
+///
+/// Returns `false` if `libgtk-layer-shell` can't be loaded or the compositor doesn't support
+/// layer-shell, leaving `window` untouched.
+pub fn init_for_window(
+  window: &gtk::Window,
+  layer: Layer,
+  attributes: &LayerShellAttributes,
+) -> bool {
+  let Some(lib) = LIBRARY
+    .get_or_init(|| unsafe { Container::load("libgtk-layer-shell.so.0").ok() })
+    .as_ref()
+  else {
+    return false;
+  };
+
+  unsafe {
+    if lib.gtk_layer_is_supported() == 0 {
+      return false;
+    }
+
+    let window: *mut GtkWindow = window.to_glib_none().0;
+    lib.gtk_layer_init_for_window(window);
+    lib.gtk_layer_set_layer(
+      window,
+      match layer {
+        Layer::Background => 0,
+        Layer::Bottom => 1,
+        Layer::Top => 2,
+        Layer::Overlay => 3,
+      },
+    );
+    for ((anchor, edge), margin) in EDGES.iter().zip(attributes.margins) {
+      lib.gtk_layer_set_anchor(window, *edge, attributes.anchor.contains(*anchor) as c_int);
+      lib.gtk_layer_set_margin(window, *edge, margin);
+    }
+    lib.gtk_layer_set_exclusive_zone(window, attributes.exclusive_zone);
+    lib.gtk_layer_set_keyboard_mode(
+      window,
+      match attributes.keyboard_interactivity {
+        KeyboardInteractivity::None => 0,
+        KeyboardInteractivity::Exclusive => 1,
+        KeyboardInteractivity::OnDemand => 2,
+      },
+    );
+  }
+
+  true
+}
@@ -3,3 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod header;
+pub mod layer_shell;
+pub mod pointer_constraints;
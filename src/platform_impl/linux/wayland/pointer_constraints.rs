@@ -0,0 +1,286 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal client of the `zwp_pointer_constraints_v1` protocol.
+//!
+//! GTK 3 doesn't expose pointer constraints, so the requests are marshalled directly through
+//! the `libwayland-client` connection owned by gdk, on a private event queue.
+
+use std::{
+  ffi::{c_char, c_int, c_void, CStr},
+  ptr,
+  sync::OnceLock,
+};
+
+use dlopen2::wrapper::{Container, WrapperApi};
+
+#[repr(C)]
+struct WlMessage {
+  name: *const c_char,
+  signature: *const c_char,
+  types: *const *const WlInterface,
+}
+
+#[repr(C)]
+pub struct WlInterface {
+  name: *const c_char,
+  version: c_int,
+  method_count: c_int,
+  methods: *const WlMessage,
+  event_count: c_int,
+  events: *const WlMessage,
+}
+
+// Interfaces only point to static data.
+unsafe impl Sync for WlInterface {}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+union WlArgument {
+  u: u32,
+  s: *const c_char,
+  o: *mut c_void,
+}
+
+#[repr(C)]
+struct RegistryListener {
+  global: unsafe extern "C" fn(*mut c_void, *mut c_void, u32, *const c_char, u32),
+  global_remove: unsafe extern "C" fn(*mut c_void, *mut c_void, u32),
+}
+
+#[derive(WrapperApi)]
+struct WaylandClient<'a> {
+  wl_display_create_queue: unsafe extern "C" fn(display: *mut c_void) -> *mut c_void,
+  wl_display_roundtrip_queue:
+    unsafe extern "C" fn(display: *mut c_void, queue: *mut c_void) -> c_int,
+  wl_display_flush: unsafe extern "C" fn(display: *mut c_void) -> c_int,
+  wl_event_queue_destroy: unsafe extern "C" fn(queue: *mut c_void),
+  wl_proxy_create_wrapper: unsafe extern "C" fn(proxy: *mut c_void) -> *mut c_void,
+  wl_proxy_wrapper_destroy: unsafe extern "C" fn(wrapper: *mut c_void),
+  wl_proxy_set_queue: unsafe extern "C" fn(proxy: *mut c_void, queue: *mut c_void),
+  wl_proxy_add_listener: unsafe extern "C" fn(
+    proxy: *mut c_void,
+    implementation: *const c_void,
+    data: *mut c_void,
+  ) -> c_int,
+  wl_proxy_marshal_array:
+    unsafe extern "C" fn(proxy: *mut c_void, opcode: u32, args: *mut WlArgument),
+  wl_proxy_marshal_array_constructor: unsafe extern "C" fn(
+    proxy: *mut c_void,
+    opcode: u32,
+    args: *mut WlArgument,
+    interface: *const WlInterface,
+  ) -> *mut c_void,
+  wl_proxy_destroy: unsafe extern "C" fn(proxy: *mut c_void),
+  wl_registry_interface: &'a WlInterface,
+}
+
+/// Wrapper making the protocol tables, which only contain pointers to static data, shareable.
+#[repr(transparent)]
+struct Static<T>(T);
+unsafe impl<T> Sync for Static<T> {}
+
+macro_rules! cstr {
+  ($s:literal) => {
+    concat!($s, "\0").as_ptr() as *const c_char
+  };
+}
+
+// The longest signature is 5 arguments long. Interface types are only used by libwayland for
+// debug output, so they are left unknown.
+static NULL_TYPES: Static<[*const WlInterface; 5]> = Static([ptr::null(); 5]);
+
+macro_rules! message {
+  ($name:literal, $signature:literal) => {
+    WlMessage {
+      name: cstr!($name),
+      signature: cstr!($signature),
+      types: &NULL_TYPES.0 as *const _ as *const *const WlInterface,
+    }
+  };
+}
+
+static POINTER_CONSTRAINTS_REQUESTS: Static<[WlMessage; 3]> = Static([
+  message!("destroy", ""),
+  message!("lock_pointer", "noo?ou"),
+  message!("confine_pointer", "noo?ou"),
+]);
+
+static POINTER_CONSTRAINTS_INTERFACE: Static<WlInterface> = Static(WlInterface {
+  name: cstr!("zwp_pointer_constraints_v1"),
+  version: 1,
+  method_count: 3,
+  methods: &POINTER_CONSTRAINTS_REQUESTS.0 as *const _ as *const WlMessage,
+  event_count: 0,
+  events: ptr::null(),
+});
+
+static LOCKED_POINTER_REQUESTS: Static<[WlMessage; 3]> = Static([
+  message!("destroy", ""),
+  message!("set_cursor_position_hint", "ff"),
+  message!("set_region", "?o"),
+]);
+
+static LOCKED_POINTER_EVENTS: Static<[WlMessage; 2]> =
+  Static([message!("locked", ""), message!("unlocked", "")]);
+
+static LOCKED_POINTER_INTERFACE: Static<WlInterface> = Static(WlInterface {
+  name: cstr!("zwp_locked_pointer_v1"),
+  version: 1,
+  method_count: 3,
+  methods: &LOCKED_POINTER_REQUESTS.0 as *const _ as *const WlMessage,
+  event_count: 2,
+  events: &LOCKED_POINTER_EVENTS.0 as *const _ as *const WlMessage,
+});
+
+static REGISTRY_LISTENER: RegistryListener = RegistryListener {
+  global: registry_global,
+  global_remove: registry_global_remove,
+};
+
+const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+const WL_REGISTRY_BIND: u32 = 0;
+const POINTER_CONSTRAINTS_DESTROY: u32 = 0;
+const POINTER_CONSTRAINTS_LOCK_POINTER: u32 = 1;
+const LOCKED_POINTER_DESTROY: u32 = 0;
+const LIFETIME_PERSISTENT: u32 = 2;
+
+unsafe extern "C" fn registry_global(
+  data: *mut c_void,
+  _registry: *mut c_void,
+  name: u32,
+  interface: *const c_char,
+  _version: u32,
+) {
+  if CStr::from_ptr(interface).to_bytes() == b"zwp_pointer_constraints_v1" {
+    *(data as *mut Option<u32>) = Some(name);
+  }
+}
+
+unsafe extern "C" fn registry_global_remove(_data: *mut c_void, _registry: *mut c_void, _: u32) {}
+
+/// The library is loaded on first use and kept, as gdk keeps it loaded anyway.
+static LIBRARY: OnceLock<Option<Container<WaylandClient<'static>>>> = OnceLock::new();
+
+/// A pointer locked in place on a surface. The lock is released on drop.
+pub struct LockedPointer {
+  lib: &'static Container<WaylandClient<'static>>,
+  display: *mut c_void,
+  queue: *mut c_void,
+  constraints: *mut c_void,
+  locked_pointer: *mut c_void,
+}
+
+impl LockedPointer {
+  /// Locks `pointer` on `surface`, both belonging to the `display` connection.
+  ///
+  /// Returns `None` if `libwayland-client` can't be loaded or the compositor doesn't support
+  /// the pointer constraints protocol.
+  ///
+  /// # Safety
+  ///
+  /// All the pointers must be valid and outlive the returned lock.
+  pub unsafe fn new(
+    display: *mut c_void,
+    surface: *mut c_void,
+    pointer: *mut c_void,
+  ) -> Option<Self> {
+    let lib = LIBRARY
+      .get_or_init(|| Container::load("libwayland-client.so.0").ok())
+      .as_ref()?;
+
+    let queue = lib.wl_display_create_queue(display);
+    let wrapper = lib.wl_proxy_create_wrapper(display);
+    lib.wl_proxy_set_queue(wrapper, queue);
+    let mut args = [WlArgument { o: ptr::null_mut() }];
+    let registry = lib.wl_proxy_marshal_array_constructor(
+      wrapper,
+      WL_DISPLAY_GET_REGISTRY,
+      args.as_mut_ptr(),
+      lib.wl_registry_interface(),
+    );
+    lib.wl_proxy_wrapper_destroy(wrapper);
+
+    let mut global: Option<u32> = None;
+    lib.wl_proxy_add_listener(
+      registry,
+      &REGISTRY_LISTENER as *const _ as *const c_void,
+      &mut global as *mut _ as *mut c_void,
+    );
+    lib.wl_display_roundtrip_queue(display, queue);
+
+    let constraints = global.map(|name| {
+      let mut args = [
+        WlArgument { u: name },
+        WlArgument {
+          s: POINTER_CONSTRAINTS_INTERFACE.0.name,
+        },
+        WlArgument { u: 1 },
+        WlArgument { o: ptr::null_mut() },
+      ];
+      lib.wl_proxy_marshal_array_constructor(
+        registry,
+        WL_REGISTRY_BIND,
+        args.as_mut_ptr(),
+        &POINTER_CONSTRAINTS_INTERFACE.0,
+      )
+    });
+    // The bound global outlives the registry, and no more registry events are dispatched.
+    lib.wl_proxy_destroy(registry);
+
+    let Some(constraints) = constraints.filter(|c| !c.is_null()) else {
+      lib.wl_event_queue_destroy(queue);
+      return None;
+    };
+
+    let mut args = [
+      WlArgument { o: ptr::null_mut() },
+      WlArgument { o: surface },
+      WlArgument { o: pointer },
+      WlArgument { o: ptr::null_mut() },
+      WlArgument {
+        u: LIFETIME_PERSISTENT,
+      },
+    ];
+    let locked_pointer = lib.wl_proxy_marshal_array_constructor(
+      constraints,
+      POINTER_CONSTRAINTS_LOCK_POINTER,
+      args.as_mut_ptr(),
+      &LOCKED_POINTER_INTERFACE.0,
+    );
+    lib.wl_display_flush(display);
+
+    Some(Self {
+      lib,
+      display,
+      queue,
+      constraints,
+      locked_pointer,
+    })
+  }
+}
+
+impl Drop for LockedPointer {
+  fn drop(&mut self) {
+    unsafe {
+      let mut args = [WlArgument { u: 0 }];
+      if !self.locked_pointer.is_null() {
+        self.lib.wl_proxy_marshal_array(
+          self.locked_pointer,
+          LOCKED_POINTER_DESTROY,
+          args.as_mut_ptr(),
+        );
+        self.lib.wl_proxy_destroy(self.locked_pointer);
+      }
+      self.lib.wl_proxy_marshal_array(
+        self.constraints,
+        POINTER_CONSTRAINTS_DESTROY,
+        args.as_mut_ptr(),
+      );
+      self.lib.wl_proxy_destroy(self.constraints);
+      self.lib.wl_display_flush(self.display);
+      self.lib.wl_event_queue_destroy(self.queue);
+    }
+  }
+}
@@ -24,17 +24,18 @@ use crate::{
   error::{ExternalError, NotSupportedError, OsError as RootOsError},
   icon::Icon,
   monitor::MonitorHandle as RootMonitorHandle,
-  platform_impl::wayland::header::WlHeader,
+  platform::unix::{KeyboardInteractivity, OsErrorKind, WindowType},
+  platform_impl::wayland::{header::WlHeader, layer_shell, pointer_constraints::LockedPointer},
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
+    CenterOn, CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
     WindowAttributes, WindowSizeConstraints, RGBA,
   },
 };
 
 use super::{
-  event_loop::EventLoopWindowTarget,
+  event_loop::{CursorPosition, EventLoopWindowTarget},
   monitor::{self, MonitorHandle},
-  util, Parent, PlatformSpecificWindowBuilderAttributes,
+  util, x11, OsError, Parent, PlatformSpecificWindowBuilderAttributes,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -50,6 +51,22 @@ impl WindowId {
 // ref: https://github.com/WebKit/WebKit/blob/e44ffaa0d999a9807f76f1805943eea204cfdfbc/Source/WebKit/UIProcess/API/gtk/PageClientImpl.cpp#L587
 const GTK_THEME_SUFFIX_LIST: [&'static str; 3] = ["-dark", "-Dark", "-Darker"];
 
+/// Returns the theme currently selected in the GTK settings.
+pub(crate) fn theme_from_settings(settings: &Settings) -> Theme {
+  if settings.is_gtk_application_prefer_dark_theme() {
+    return Theme::Dark;
+  }
+
+  if let Some(theme) = settings.gtk_theme_name() {
+    let theme = theme.as_str();
+    if GTK_THEME_SUFFIX_LIST.iter().any(|t| theme.ends_with(t)) {
+      return Theme::Dark;
+    }
+  }
+
+  Theme::Light
+}
+
 pub struct Window {
   /// Window id.
   pub(crate) window_id: WindowId,
@@ -72,6 +89,10 @@ pub struct Window {
   draw_tx: crossbeam_channel::Sender<WindowId>,
   preferred_theme: RefCell<Option<Theme>>,
   css_provider: CssProvider,
+  /// Cursor position on Wayland, shared with the event loop.
+  cursor_position: CursorPosition,
+  /// Pointer lock held while the cursor is grabbed on Wayland.
+  cursor_lock: RefCell<Option<LockedPointer>>,
 }
 
 impl Window {
@@ -90,14 +111,48 @@ impl Window {
       .accept_focus(attributes.focused);
     if let Parent::ChildOf(parent) = pl_attribs.parent {
       window_builder = window_builder.transient_for(&parent);
+      if attributes.position.is_none() {
+        window_builder = window_builder.window_position(gtk::WindowPosition::CenterOnParent);
+      }
     }
 
     let window = window_builder.build();
 
-    if is_wayland {
+    let layer_shell = match pl_attribs.layer {
+      Some(layer) if is_wayland => {
+        let initialized =
+          layer_shell::init_for_window(window.upcast_ref(), layer, &pl_attribs.layer_shell);
+        if !initialized {
+          log::warn!("Layer-shell is not available, creating a normal window instead");
+        }
+        initialized
+      }
+      Some(layer) => {
+        util::setup_dock(&window, layer, pl_attribs.layer_shell);
+        true
+      }
+      None => false,
+    };
+
+    if is_wayland && !layer_shell {
       WlHeader::setup(&window, &attributes.title);
     }
 
+    if let Some(window_type) = pl_attribs.window_type {
+      window.set_type_hint(util::window_type_hint(window_type));
+    }
+    if pl_attribs.urgent {
+      window.set_urgency_hint(true);
+    }
+    if pl_attribs.override_redirect {
+      // Set once realized, before the window is mapped.
+      window.connect_realize(|window| {
+        if let Some(gdk_window) = window.window() {
+          gdk_window.set_override_redirect(true);
+        }
+      });
+    }
+
     let window_id = WindowId(window.id());
     event_loop_window_target
       .windows
@@ -124,6 +179,11 @@ impl Window {
     }
 
     window.set_deletable(attributes.closable);
+    util::set_window_buttons(
+      &window,
+      Some(attributes.minimizable),
+      Some(attributes.maximizable),
+    );
 
     // Set Min/Max Size
     util::set_size_constraints(&window, attributes.inner_size_constraints);
@@ -232,7 +292,11 @@ impl Window {
 
     // restore accept-focus after the window has been drawn
     // if the window was initially created without focus
-    if !attributes.focused {
+    // Docks that never take focus keep accept-focus disabled.
+    let unfocusable_dock = !is_wayland
+      && layer_shell
+      && pl_attribs.layer_shell.keyboard_interactivity == KeyboardInteractivity::None;
+    if !attributes.focused && !unfocusable_dock {
       let signal_id = Arc::new(RefCell::new(None));
       let signal_id_ = signal_id.clone();
       let id = window.connect_draw(move |window, _| {
@@ -295,11 +359,21 @@ impl Window {
       inner_size_constraints: RefCell::new(attributes.inner_size_constraints),
       preferred_theme: RefCell::new(preferred_theme),
       css_provider: CssProvider::new(),
+      cursor_lock: RefCell::new(None),
+      cursor_position: event_loop_window_target.cursor_position.clone(),
     };
 
     let _ = win.set_skip_taskbar(pl_attribs.skip_taskbar);
+    if pl_attribs.skip_pager {
+      win.set_skip_pager(true);
+    }
     win.set_background_color(attributes.background_color);
 
+    // Exclusive fullscreen changes the video mode, which is done by the event loop.
+    if let Some(Fullscreen::Exclusive(_)) = win.fullscreen() {
+      win.set_fullscreen(win.fullscreen());
+    }
+
     Ok(win)
   }
 
@@ -384,6 +458,12 @@ impl Window {
       scale_factor_clone.store(window.scale_factor(), Ordering::Release);
     });
 
+    // GTK resets the window manager hints when these change or the window is realized.
+    window.connect_resizable_notify(util::update_window_buttons);
+    window.connect_deletable_notify(util::update_window_buttons);
+    window.connect_realize(util::update_window_buttons);
+    util::update_window_buttons(window);
+
     (
       scale_factor,
       outer_position,
@@ -438,6 +518,8 @@ impl Window {
       inner_size_constraints: RefCell::new(WindowSizeConstraints::default()),
       preferred_theme: RefCell::new(None),
       css_provider: CssProvider::new(),
+      cursor_lock: RefCell::new(None),
+      cursor_position: event_loop_window_target.cursor_position.clone(),
     };
 
     Ok(win)
@@ -607,9 +689,23 @@ impl Window {
     }
   }
 
-  pub fn set_minimizable(&self, _minimizable: bool) {}
+  pub fn set_minimizable(&self, minimizable: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Minimizable(minimizable)))
+    {
+      log::warn!("Fail to send minimizable request: {}", e);
+    }
+  }
 
-  pub fn set_maximizable(&self, _maximizable: bool) {}
+  pub fn set_maximizable(&self, maximizable: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::Maximizable(maximizable)))
+    {
+      log::warn!("Fail to send maximizable request: {}", e);
+    }
+  }
 
   pub fn set_closable(&self, closable: bool) {
     if let Err(e) = self
@@ -657,12 +753,13 @@ impl Window {
   }
 
   pub fn is_minimizable(&self) -> bool {
-    true
+    util::is_minimizable(&self.window)
   }
 
   pub fn is_maximizable(&self) -> bool {
-    true
+    util::is_maximizable(&self.window)
   }
+
   pub fn is_closable(&self) -> bool {
     self.window.is_deletable()
   }
@@ -746,8 +843,18 @@ impl Window {
     }
   }
 
-  pub fn set_ime_position<P: Into<Position>>(&self, _position: P) {
-    //TODO
+  pub fn set_ime_position<P: Into<Position>>(&self, position: P) {
+    let (x, y): (i32, i32) = position
+      .into()
+      .to_logical::<i32>(self.scale_factor())
+      .into();
+
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::ImePosition((x, y))))
+    {
+      log::warn!("Fail to send IME position request: {}", e);
+    }
   }
 
   pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
@@ -777,6 +884,12 @@ impl Window {
   }
 
   pub fn set_cursor_position<P: Into<Position>>(&self, position: P) -> Result<(), ExternalError> {
+    if self.is_wayland() {
+      return Err(ExternalError::Os(os_error!(OsError::new(
+        OsErrorKind::WaylandUnsupported,
+        "Wayland doesn't allow moving the cursor"
+      ))));
+    }
     let inner_pos = self.inner_position().unwrap_or_default();
     let (x, y): (i32, i32) = position
       .into()
@@ -793,8 +906,103 @@ impl Window {
     Ok(())
   }
 
-  pub fn set_cursor_grab(&self, _grab: bool) -> Result<(), ExternalError> {
-    Ok(())
+  pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
+    let Some(window) = self.window.window() else {
+      return Err(ExternalError::Os(os_error!(OsError::new(
+        OsErrorKind::NotRealized,
+        "The window isn't realized yet"
+      ))));
+    };
+    let display = self.window.display();
+
+    if self.is_wayland() {
+      let mut cursor_lock = self.cursor_lock.borrow_mut();
+      if !grab {
+        cursor_lock.take();
+        return Ok(());
+      }
+      if cursor_lock.is_some() {
+        return Ok(());
+      }
+
+      let Some(pointer) = display.default_seat().and_then(|seat| seat.pointer()) else {
+        return Err(ExternalError::Os(os_error!(OsError::new(
+          OsErrorKind::Other,
+          "No pointer device found"
+        ))));
+      };
+      // The surface is only created once the window is mapped, and the protocol doesn't allow
+      // locking on a null one.
+      let surface =
+        unsafe { gdk_wayland_sys::gdk_wayland_window_get_wl_surface(window.as_ptr() as *mut _) };
+      if surface.is_null() {
+        return Err(ExternalError::Os(os_error!(OsError::new(
+          OsErrorKind::NotRealized,
+          "The window has no Wayland surface yet"
+        ))));
+      }
+      let lock = unsafe {
+        LockedPointer::new(
+          gdk_wayland_sys::gdk_wayland_display_get_wl_display(display.as_ptr() as *mut _),
+          surface as *mut _,
+          gdk_wayland_sys::gdk_wayland_device_get_wl_pointer(pointer.as_ptr() as *mut _),
+        )
+      };
+      match lock {
+        Some(lock) => {
+          *cursor_lock = Some(lock);
+          Ok(())
+        }
+        None => Err(ExternalError::Os(os_error!(OsError::new(
+          OsErrorKind::WaylandUnsupported,
+          "The compositor doesn't support the pointer constraints protocol"
+        )))),
+      }
+    } else if display.backend().is_x11() {
+      use x11_dl::xlib;
+
+      let Some(xlib) = x11::xlib() else {
+        return Err(ExternalError::NotSupported(NotSupportedError::new()));
+      };
+      unsafe {
+        // Grab through gdk's own connection, as X11 grabs are released with their client.
+        let xdisplay = gdk_x11_sys::gdk_x11_display_get_xdisplay(display.as_ptr() as *mut _)
+          as *mut xlib::Display;
+        let result = if grab {
+          let xid = gdk_x11_sys::gdk_x11_window_get_xid(window.as_ptr() as *mut _);
+          (xlib.XGrabPointer)(
+            xdisplay,
+            xid,
+            xlib::True,
+            (xlib::ButtonPressMask
+              | xlib::ButtonReleaseMask
+              | xlib::EnterWindowMask
+              | xlib::LeaveWindowMask
+              | xlib::PointerMotionMask) as _,
+            xlib::GrabModeAsync,
+            xlib::GrabModeAsync,
+            xid,
+            0,
+            xlib::CurrentTime,
+          )
+        } else {
+          (xlib.XUngrabPointer)(xdisplay, xlib::CurrentTime);
+          xlib::GrabSuccess
+        };
+        (xlib.XFlush)(xdisplay);
+
+        if result == xlib::GrabSuccess {
+          Ok(())
+        } else {
+          Err(ExternalError::Os(os_error!(OsError::new(
+            OsErrorKind::Other,
+            format!("Failed to grab the pointer, grab status {}", result)
+          ))))
+        }
+      }
+    } else {
+      Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
   }
 
   pub fn set_ignore_cursor_events(&self, ignore: bool) -> Result<(), ExternalError> {
@@ -824,7 +1032,14 @@ impl Window {
 
   #[inline]
   pub fn cursor_position(&self) -> Result<PhysicalPosition<f64>, ExternalError> {
-    util::cursor_position(self.is_wayland())
+    if self.is_wayland() {
+      match self.cursor_position.get() {
+        Some((id, position)) if id == self.window_id => Ok(position),
+        _ => Err(ExternalError::NotSupported(NotSupportedError::new())),
+      }
+    } else {
+      util::cursor_position()
+    }
   }
 
   pub fn current_monitor(&self) -> Option<RootMonitorHandle> {
@@ -986,6 +1201,24 @@ impl Window {
     }
   }
 
+  pub fn wayland_surface(&self) -> Option<*mut std::ffi::c_void> {
+    if !self.is_wayland() {
+      return None;
+    }
+    let window = self.window.window()?;
+    let surface =
+      unsafe { gdk_wayland_sys::gdk_wayland_window_get_wl_surface(window.as_ptr() as *mut _) };
+    (!surface.is_null()).then_some(surface)
+  }
+
+  pub fn xlib_window(&self) -> Option<std::ffi::c_ulong> {
+    if !self.window.display().backend().is_x11() {
+      return None;
+    }
+    let window = self.window.window()?;
+    Some(unsafe { gdk_x11_sys::gdk_x11_window_get_xid(window.as_ptr() as *mut _) })
+  }
+
   pub fn set_skip_taskbar(&self, skip: bool) -> Result<(), ExternalError> {
     if let Err(e) = self
       .window_requests_tx
@@ -997,6 +1230,42 @@ impl Window {
     Ok(())
   }
 
+  pub fn center_on(&self, target: CenterOn) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::CenterOn(target)))
+    {
+      log::warn!("Fail to send center on request: {}", e);
+    }
+  }
+
+  pub fn set_window_type(&self, window_type: WindowType) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::WindowType(window_type)))
+    {
+      log::warn!("Fail to send window type request: {}", e);
+    }
+  }
+
+  pub fn set_skip_pager(&self, skip: bool) {
+    if let Err(e) = self
+      .window_requests_tx
+      .send((self.window_id, WindowRequest::SkipPager(skip)))
+    {
+      log::warn!("Fail to send skip pager request: {}", e);
+    }
+  }
+
+  pub fn set_override_redirect(&self, override_redirect: bool) {
+    if let Err(e) = self.window_requests_tx.send((
+      self.window_id,
+      WindowRequest::OverrideRedirect(override_redirect),
+    )) {
+      log::warn!("Fail to send override redirect request: {}", e);
+    }
+  }
+
   pub fn set_progress_bar(&self, progress: ProgressBarState) {
     if let Err(e) = self
       .window_requests_tx
@@ -1020,14 +1289,9 @@ impl Window {
       return theme;
     }
 
-    if let Some(theme) = Settings::default().and_then(|s| s.gtk_theme_name()) {
-      let theme = theme.as_str();
-      if GTK_THEME_SUFFIX_LIST.iter().any(|t| theme.ends_with(t)) {
-        return Theme::Dark;
-      }
-    }
-
-    Theme::Light
+    Settings::default()
+      .map(|settings| theme_from_settings(&settings))
+      .unwrap_or(Theme::Light)
   }
 
   pub fn set_theme(&self, theme: Option<Theme>) {
@@ -1050,11 +1314,14 @@ unsafe impl Sync for Window {}
 pub enum WindowRequest {
   Title(String),
   Position((i32, i32)),
+  CenterOn(CenterOn),
   Size((i32, i32)),
   SizeConstraints(WindowSizeConstraints),
   Visible(bool),
   Focus,
   Resizable(bool),
+  Minimizable(bool),
+  Maximizable(bool),
   Closable(bool),
   Minimized(bool),
   Maximized(bool, bool),
@@ -1067,9 +1334,13 @@ pub enum WindowRequest {
   WindowIcon(Option<Icon>),
   UserAttention(Option<UserAttentionType>),
   SetSkipTaskbar(bool),
+  SkipPager(bool),
+  WindowType(WindowType),
+  OverrideRedirect(bool),
   CursorIcon(Option<CursorIcon>),
   CursorPosition((i32, i32)),
   CursorIgnoreEvents(bool),
+  ImePosition((i32, i32)),
   WireUpEvents {
     transparent: bool,
     fullscreen: bool,
@@ -0,0 +1,315 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+  cell::RefCell,
+  collections::{HashMap, HashSet},
+  mem::MaybeUninit,
+  os::raw::{c_int, c_uint},
+  rc::Rc,
+  slice,
+  sync::Arc,
+};
+
+use gtk::glib;
+
+use super::{ffi, xdisplay::XError, XConnection};
+use crate::{
+  accelerator::{Accelerator, GlobalHotKeyId},
+  platform::unix::OsErrorKind,
+  platform_impl::{keycode_to_scancode, OsError},
+};
+
+/// The modifiers a hotkey can use, other modifiers like the lock keys are ignored.
+const HOTKEY_MODIFIERS: c_uint = ffi::ShiftMask | ffi::ControlMask | ffi::Mod1Mask | ffi::Mod4Mask;
+
+impl XConnection {
+  /// Returns the modifier mask `keysym` is mapped to, or 0 if it isn't a modifier.
+  pub fn modifier_mask(&self, keysym: c_uint) -> c_uint {
+    unsafe {
+      let keycode = (self.xlib.XKeysymToKeycode)(self.display, keysym as ffi::KeySym);
+      if keycode == 0 {
+        return 0;
+      }
+      let map = (self.xlib.XGetModifierMapping)(self.display);
+      if map.is_null() {
+        return 0;
+      }
+
+      let keys_per_modifier = (*map).max_keypermod as usize;
+      let keycodes = slice::from_raw_parts((*map).modifiermap, 8 * keys_per_modifier);
+      let mask = keycodes
+        .chunks(keys_per_modifier)
+        .position(|keycodes| keycodes.contains(&keycode))
+        .map_or(0, |modifier| 1 << modifier);
+      (self.xlib.XFreeModifiermap)(map);
+      mask
+    }
+  }
+
+  /// Returns the combinations of Caps Lock and Num Lock, which are grabbed along with the
+  /// modifiers of a key so it's received whatever the state of the lock keys.
+  fn lock_combinations(&self) -> Vec<c_uint> {
+    let num_lock = self.modifier_mask(ffi::XK_Num_Lock);
+    let mut locks = vec![0, ffi::LockMask, num_lock, ffi::LockMask | num_lock];
+    locks.sort_unstable();
+    locks.dedup();
+    locks
+  }
+
+  /// Grabs `keycode` with `modifiers` on the root window, so its presses are received even when
+  /// no window of the app is focused.
+  pub fn grab_key(&self, keycode: c_int, modifiers: c_uint) -> Result<(), XError> {
+    unsafe {
+      let root = (self.xlib.XDefaultRootWindow)(self.display);
+      for lock in self.lock_combinations() {
+        (self.xlib.XGrabKey)(
+          self.display,
+          keycode,
+          modifiers | lock,
+          root,
+          ffi::False,
+          ffi::GrabModeAsync,
+          ffi::GrabModeAsync,
+        );
+      }
+      (self.xlib.XSync)(self.display, ffi::False);
+    }
+
+    // Grabbing fails with `BadAccess` if another client grabbed the key.
+    self.check_errors().map_err(|e| {
+      self.ungrab_key(keycode, modifiers);
+      self.ignore_error();
+      e
+    })
+  }
+
+  /// Releases a key grabbed with [`XConnection::grab_key`].
+  pub fn ungrab_key(&self, keycode: c_int, modifiers: c_uint) {
+    unsafe {
+      let root = (self.xlib.XDefaultRootWindow)(self.display);
+      for lock in self.lock_combinations() {
+        (self.xlib.XUngrabKey)(self.display, keycode, modifiers | lock, root);
+      }
+      (self.xlib.XSync)(self.display, ffi::False);
+    }
+  }
+}
+
+/// The global hotkeys of an event loop, grabbed on the shared X connection.
+pub struct GlobalHotKeys {
+  state: Rc<RefCell<HotKeyState>>,
+  next_id: u32,
+  on_press: Rc<dyn Fn(GlobalHotKeyId)>,
+  /// Takes the hotkey events from the X connection, added when the first hotkey is registered.
+  watch: Option<glib::SourceId>,
+}
+
+/// The registered hotkeys and the state of their keys.
+#[derive(Default)]
+struct HotKeyState {
+  /// The registered hotkeys by X keycode and modifiers.
+  hotkeys: HashMap<(c_uint, c_uint), GlobalHotKeyId>,
+  /// The grabbed keys held down, to ignore their auto-repeated presses.
+  pressed: HashSet<c_uint>,
+}
+
+impl GlobalHotKeys {
+  pub fn new(on_press: impl Fn(GlobalHotKeyId) + 'static) -> Self {
+    Self {
+      state: Default::default(),
+      next_id: 0,
+      on_press: Rc::new(on_press),
+      watch: None,
+    }
+  }
+
+  pub fn register(
+    &mut self,
+    xconn: &Arc<XConnection>,
+    accelerator: Accelerator,
+  ) -> Result<GlobalHotKeyId, OsError> {
+    let Some(keycode) = keycode_to_scancode(accelerator.key()) else {
+      return Err(OsError::new(
+        OsErrorKind::Other,
+        format!("The {} key has no X keycode", accelerator.key()),
+      ));
+    };
+    let modifiers = accelerator_mask(&accelerator);
+    if self
+      .state
+      .borrow()
+      .hotkeys
+      .contains_key(&(keycode, modifiers))
+    {
+      return Err(OsError::new(
+        OsErrorKind::Other,
+        format!("The {} hotkey is already registered", accelerator),
+      ));
+    }
+
+    xconn.grab_key(keycode as c_int, modifiers)?;
+
+    let id = GlobalHotKeyId(self.next_id);
+    self.next_id += 1;
+    self
+      .state
+      .borrow_mut()
+      .hotkeys
+      .insert((keycode, modifiers), id);
+
+    if self.watch.is_none() {
+      // Held keys send presses without releases in between, which tells auto-repeat apart.
+      let mut supported = ffi::False;
+      unsafe { (xconn.xlib.XkbSetDetectableAutoRepeat)(xconn.display, ffi::True, &mut supported) };
+      if supported == ffi::False {
+        log::warn!("Detectable auto-repeat isn't supported, held hotkeys will repeat");
+      }
+
+      let xconn = xconn.clone();
+      let state = self.state.clone();
+      let on_press = self.on_press.clone();
+      self.watch = Some(glib::source::unix_fd_add_local(
+        xconn.x11_fd,
+        glib::IOCondition::IN,
+        move |_, _| {
+          dispatch_key_events(&xconn, &mut state.borrow_mut(), &*on_press);
+          glib::ControlFlow::Continue
+        },
+      ));
+    }
+    // Events read while syncing the grab are queued by xlib without waking up the watch.
+    dispatch_key_events(xconn, &mut self.state.borrow_mut(), &*self.on_press);
+
+    Ok(id)
+  }
+
+  pub fn unregister(&mut self, xconn: &XConnection, id: GlobalHotKeyId) {
+    let mut state = self.state.borrow_mut();
+    let Some(&(keycode, modifiers)) = state
+      .hotkeys
+      .iter()
+      .find_map(|(hotkey, hotkey_id)| (*hotkey_id == id).then_some(hotkey))
+    else {
+      return;
+    };
+    state.hotkeys.remove(&(keycode, modifiers));
+    state.pressed.remove(&keycode);
+    xconn.ungrab_key(keycode as c_int, modifiers);
+  }
+}
+
+/// Returns the X modifier mask of the modifiers of `accelerator`.
+fn accelerator_mask(accelerator: &Accelerator) -> c_uint {
+  let mut mask = 0;
+  for (pressed, modifier) in [
+    (accelerator.mods().shift_key(), ffi::ShiftMask),
+    (accelerator.mods().control_key(), ffi::ControlMask),
+    (accelerator.mods().alt_key(), ffi::Mod1Mask),
+    (accelerator.mods().super_key(), ffi::Mod4Mask),
+  ] {
+    if pressed {
+      mask |= modifier;
+    }
+  }
+  mask
+}
+
+/// Takes the events of the registered hotkeys out of the event queue of the X connection, calling
+/// `on_press` for the keys that weren't already held down. Other events are left to the other
+/// users of the connection.
+fn dispatch_key_events(
+  xconn: &XConnection,
+  state: &mut HotKeyState,
+  on_press: &dyn Fn(GlobalHotKeyId),
+) {
+  unsafe extern "C" fn is_hotkey_event(
+    _: *mut ffi::Display,
+    event: *mut ffi::XEvent,
+    state: ffi::XPointer,
+  ) -> ffi::Bool {
+    let state = &*(state as *const HotKeyState);
+    state.is_hotkey_event(&*event) as ffi::Bool
+  }
+
+  unsafe {
+    let mut event = MaybeUninit::<ffi::XEvent>::uninit();
+    // Reads the available events into the queue before searching it.
+    while (xconn.xlib.XCheckIfEvent)(
+      xconn.display,
+      event.as_mut_ptr(),
+      Some(is_hotkey_event),
+      state as *mut HotKeyState as ffi::XPointer,
+    ) != 0
+    {
+      let mut event = event.assume_init();
+      match event.get_type() {
+        ffi::KeyPress => {
+          let key: &ffi::XKeyEvent = event.as_ref();
+          if state.pressed.insert(key.keycode) {
+            if let Some(id) = state
+              .hotkeys
+              .get(&(key.keycode, key.state & HOTKEY_MODIFIERS))
+            {
+              on_press(*id);
+            }
+          }
+        }
+        ffi::KeyRelease => {
+          let key: &ffi::XKeyEvent = event.as_ref();
+          state.pressed.remove(&key.keycode);
+        }
+        ffi::MappingNotify => {
+          (xconn.xlib.XRefreshKeyboardMapping)(event.as_mut());
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+impl HotKeyState {
+  /// Returns `true` if `event` is the press of a registered hotkey, the release of a grabbed key
+  /// or a change of the mapping of a grabbed key.
+  fn is_hotkey_event(&self, event: &ffi::XEvent) -> bool {
+    match event.get_type() {
+      ffi::KeyPress => {
+        let key: &ffi::XKeyEvent = event.as_ref();
+        self
+          .hotkeys
+          .contains_key(&(key.keycode, key.state & HOTKEY_MODIFIERS))
+      }
+      ffi::KeyRelease => {
+        let key: &ffi::XKeyEvent = event.as_ref();
+        self.is_grabbed(key.keycode)
+      }
+      ffi::MappingNotify => {
+        let mapping: &ffi::XMappingEvent = event.as_ref();
+        let keycodes = mapping.first_keycode..mapping.first_keycode + mapping.count;
+        mapping.request == ffi::MappingKeyboard
+          && keycodes
+            .into_iter()
+            .any(|keycode| self.is_grabbed(keycode as c_uint))
+      }
+      _ => false,
+    }
+  }
+
+  fn is_grabbed(&self, keycode: c_uint) -> bool {
+    self.hotkeys.keys().any(|&(grabbed, _)| grabbed == keycode)
+  }
+}
+
+impl Drop for GlobalHotKeys {
+  fn drop(&mut self) {
+    if let Some(watch) = self.watch.take() {
+      watch.remove();
+    }
+    if let Some(xconn) = super::opened_xconnection() {
+      for &(keycode, modifiers) in self.state.borrow().hotkeys.keys() {
+        xconn.ungrab_key(keycode as c_int, modifiers);
+      }
+    }
+  }
+}
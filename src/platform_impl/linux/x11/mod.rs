@@ -3,6 +3,108 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ffi;
+pub mod hotkey;
+pub mod randr;
 pub mod xdisplay;
 
-pub use xdisplay::XConnection;
+use std::{
+  ffi::CStr,
+  os::raw::{c_char, c_int},
+  sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+pub use xdisplay::{XConnection, XError};
+
+static XCONNECTION: OnceLock<Option<Arc<XConnection>>> = OnceLock::new();
+static XLIB: OnceLock<Option<ffi::Xlib>> = OnceLock::new();
+
+/// Returns the xlib functions, loading the library on first use.
+pub fn xlib() -> Option<&'static ffi::Xlib> {
+  XLIB.get_or_init(|| ffi::Xlib::open().ok()).as_ref()
+}
+
+/// Returns the X connection shared by the event loop, windows and monitors, opening it on first
+/// use. Returns `None` if no X server is available.
+pub fn xconnection() -> Option<Arc<XConnection>> {
+  XCONNECTION
+    .get_or_init(|| XConnection::new(Some(x_error_callback)).ok().map(Arc::new))
+    .clone()
+}
+
+/// Returns the shared X connection only if it was already opened.
+pub(crate) fn opened_xconnection() -> Option<&'static XConnection> {
+  XCONNECTION.get().and_then(|xconn| xconn.as_deref())
+}
+
+/// A hook called on X errors, returning `true` if it handled the error.
+pub type XErrorHook = Box<dyn Fn(&XError) -> bool + Send + Sync>;
+
+static X_ERROR_HOOKS: Mutex<Vec<XErrorHook>> = Mutex::new(Vec::new());
+
+/// Registers a hook called on the X errors raised in this process.
+///
+/// The errors of other X connections are only passed to hooks when no error handler was
+/// installed before tao's, otherwise they're forwarded to it, e.g. to gdk's.
+///
+/// Hooks are called in registration order until one returns `true`, in which case the error isn't
+/// logged. Errors of the shared connection are still reported by its next
+/// [`XConnection::check_errors`] either way.
+///
+/// Hooks run inside the xlib error handler, so they must not call xlib functions nor register
+/// other hooks.
+pub fn register_x_error_hook(hook: XErrorHook) {
+  X_ERROR_HOOKS
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .push(hook);
+}
+
+unsafe extern "C" fn x_error_callback(
+  display: *mut ffi::Display,
+  event: *mut ffi::XErrorEvent,
+) -> c_int {
+  let xconn = opened_xconnection();
+  if !xconn.is_some_and(|xconn| xconn.display == display) {
+    let previous_error_handler = *xdisplay::PREVIOUS_ERROR_HANDLER.lock();
+    if let Some(previous_error_handler) = previous_error_handler {
+      return previous_error_handler(display, event);
+    }
+  }
+
+  let error = XError {
+    // The connection is still being opened if this is missing, no errors are expected then.
+    description: xconn
+      .map(|xconn| {
+        let mut buffer = [0 as c_char; 1024];
+        (xconn.xlib.XGetErrorText)(
+          display,
+          (*event).error_code as c_int,
+          buffer.as_mut_ptr(),
+          buffer.len() as c_int,
+        );
+        CStr::from_ptr(buffer.as_ptr())
+          .to_string_lossy()
+          .into_owned()
+      })
+      .unwrap_or_default(),
+    error_code: (*event).error_code,
+    request_code: (*event).request_code,
+    minor_code: (*event).minor_code,
+  };
+
+  let handled = X_ERROR_HOOKS
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .iter()
+    .any(|hook| hook(&error));
+
+  if !handled {
+    error!("X11 error: {:#?}", error);
+  }
+  if let Some(xconn) = xconn.filter(|xconn| xconn.display == display) {
+    *xconn.latest_error.lock() = Some(error);
+  }
+
+  // Fun fact: this return value is completely ignored.
+  0
+}
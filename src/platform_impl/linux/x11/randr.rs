@@ -0,0 +1,158 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{os::raw::c_int, slice};
+
+use super::{ffi, xdisplay::XError, XConnection};
+
+/// A video mode of a CRTC as reported by XRandR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RandrMode {
+  pub id: ffi::RRMode,
+  pub width: u32,
+  pub height: u32,
+  /// Refresh rate in Hz, rounded to the nearest integer.
+  pub refresh_rate: u16,
+}
+
+/// A CRTC (a scanout of part of the screen to one or more outputs) and the modes it supports.
+#[derive(Debug, Clone)]
+pub struct Crtc {
+  pub id: ffi::RRCrtc,
+  pub current_mode: ffi::RRMode,
+  pub modes: Vec<RandrMode>,
+}
+
+impl XConnection {
+  /// Returns the active CRTC whose top-left corner is at `position`, in physical pixels.
+  pub fn crtc_at(&self, position: (i32, i32)) -> Option<Crtc> {
+    unsafe {
+      let root = (self.xlib.XDefaultRootWindow)(self.display);
+      let resources = (self.xrandr.XRRGetScreenResourcesCurrent)(self.display, root);
+      if resources.is_null() {
+        return None;
+      }
+
+      let res = &*resources;
+      let crtcs = slice::from_raw_parts(res.crtcs, res.ncrtc as usize);
+      let modes = slice::from_raw_parts(res.modes, res.nmode as usize);
+
+      let mut found = None;
+      for &crtc in crtcs {
+        let info = (self.xrandr.XRRGetCrtcInfo)(self.display, resources, crtc);
+        if info.is_null() {
+          continue;
+        }
+        let crtc_info = &*info;
+        if crtc_info.mode != 0 && (crtc_info.x, crtc_info.y) == position && crtc_info.noutput > 0 {
+          // All the outputs of a CRTC share its modes, so the first one is enough.
+          let output = *crtc_info.outputs;
+          let output_info = (self.xrandr.XRRGetOutputInfo)(self.display, resources, output);
+          if !output_info.is_null() {
+            let output_modes =
+              slice::from_raw_parts((*output_info).modes, (*output_info).nmode as usize);
+            let modes = output_modes
+              .iter()
+              .filter_map(|id| modes.iter().find(|mode| mode.id == *id))
+              .map(|mode| RandrMode {
+                id: mode.id,
+                width: mode.width,
+                height: mode.height,
+                refresh_rate: refresh_rate(mode),
+              })
+              .collect();
+            found = Some(Crtc {
+              id: crtc,
+              current_mode: crtc_info.mode,
+              modes,
+            });
+            (self.xrandr.XRRFreeOutputInfo)(output_info);
+          }
+        }
+        (self.xrandr.XRRFreeCrtcInfo)(info);
+        if found.is_some() {
+          break;
+        }
+      }
+
+      (self.xrandr.XRRFreeScreenResources)(resources);
+      found
+    }
+  }
+
+  /// Switches `crtc` to `mode`, keeping its position, rotation and outputs.
+  pub fn set_crtc_mode(&self, crtc: ffi::RRCrtc, mode: ffi::RRMode) -> Result<(), XError> {
+    unsafe {
+      let root = (self.xlib.XDefaultRootWindow)(self.display);
+      let resources = (self.xrandr.XRRGetScreenResourcesCurrent)(self.display, root);
+      if resources.is_null() {
+        return Err(XError {
+          description: "Failed to get the XRandR screen resources".into(),
+          error_code: 0,
+          request_code: 0,
+          minor_code: 0,
+        });
+      }
+
+      let info = (self.xrandr.XRRGetCrtcInfo)(self.display, resources, crtc);
+      let status = if info.is_null() {
+        None
+      } else {
+        let crtc_info = &*info;
+        let status = (self.xrandr.XRRSetCrtcConfig)(
+          self.display,
+          resources,
+          crtc,
+          ffi::CurrentTime,
+          crtc_info.x,
+          crtc_info.y,
+          mode,
+          crtc_info.rotation,
+          crtc_info.outputs,
+          crtc_info.noutput,
+        );
+        (self.xrandr.XRRFreeCrtcInfo)(info);
+        Some(status)
+      };
+      (self.xrandr.XRRFreeScreenResources)(resources);
+      (self.xlib.XSync)(self.display, ffi::False);
+      self.check_errors()?;
+
+      match status {
+        Some(status) if status == ffi::RRSetConfigSuccess => Ok(()),
+        _ => Err(XError {
+          description: "Failed to set the XRandR CRTC configuration".into(),
+          error_code: 0,
+          request_code: 0,
+          minor_code: 0,
+        }),
+      }
+    }
+  }
+
+  /// Returns the bit depth of the default screen.
+  pub fn default_depth(&self) -> u16 {
+    unsafe {
+      let screen = (self.xlib.XDefaultScreen)(self.display);
+      (self.xlib.XDefaultDepth)(self.display, screen) as u16
+    }
+  }
+}
+
+fn refresh_rate(mode: &ffi::XRRModeInfo) -> u16 {
+  let flags = mode.modeFlags as c_int;
+  let v_total = if flags & ffi::RR_DoubleScan != 0 {
+    mode.vTotal * 2
+  } else if flags & ffi::RR_Interlace != 0 {
+    mode.vTotal / 2
+  } else {
+    mode.vTotal
+  };
+
+  if mode.dotClock > 0 && mode.hTotal > 0 && v_total > 0 {
+    (mode.dotClock as f64 / (mode.hTotal as f64 * v_total as f64)).round() as u16
+  } else {
+    0
+  }
+}
@@ -34,6 +34,10 @@ unsafe impl Sync for XConnection {}
 pub type XErrorHandler =
   Option<unsafe extern "C" fn(*mut ffi::Display, *mut ffi::XErrorEvent) -> libc::c_int>;
 
+/// The error handler installed before the one of the X connection, e.g. gdk's. The handler is
+/// process-wide, so errors of other connections must be forwarded to it.
+pub(crate) static PREVIOUS_ERROR_HANDLER: Mutex<XErrorHandler> = parking_lot::const_mutex(None);
+
 impl XConnection {
   pub fn new(error_handler: XErrorHandler) -> Result<XConnection, XNotSupported> {
     // opening the libraries
@@ -46,7 +50,11 @@ impl XConnection {
     let xrender = ffi::Xrender::open()?;
 
     unsafe { (xlib.XInitThreads)() };
-    unsafe { (xlib.XSetErrorHandler)(error_handler) };
+    {
+      // Errors raised while swapping the handlers wait for the previous one to be known.
+      let mut previous_error_handler = PREVIOUS_ERROR_HANDLER.lock();
+      *previous_error_handler = unsafe { (xlib.XSetErrorHandler)(error_handler) };
+    }
 
     // calling XOpenDisplay
     let display = unsafe {
@@ -19,13 +19,13 @@ use cocoa::{
   foundation::{NSInteger, NSUInteger},
 };
 use core_foundation::{
-  array::CFArrayRef, data::CFDataRef, dictionary::CFDictionaryRef, string::CFStringRef,
-  uuid::CFUUIDRef,
+  array::CFArrayRef, base::CFAllocatorRef, data::CFDataRef, dictionary::CFDictionaryRef,
+  string::CFStringRef, uuid::CFUUIDRef,
 };
 use core_graphics::{
   base::CGError,
   display::{boolean_t, CGDirectDisplayID, CGDisplayConfigRef},
-  geometry::CGRect,
+  geometry::{CGRect, CGSize},
 };
 pub const NSNotFound: NSInteger = NSInteger::max_value();
 
@@ -187,6 +187,11 @@ extern "C" {
   pub fn CGDisplayCreateUUIDFromDisplayID(display: CGDirectDisplayID) -> CFUUIDRef;
 }
 
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+  pub fn CFUUIDCreateString(alloc: CFAllocatorRef, uuid: CFUUIDRef) -> CFStringRef;
+}
+
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
   pub fn CGRestorePermanentDisplayConfiguration();
@@ -222,6 +227,8 @@ extern "C" {
     mode: CGDisplayModeRef,
     options: CFDictionaryRef,
   ) -> CGError;
+  pub fn CGDisplayCopyDisplayMode(display: CGDirectDisplayID) -> CGDisplayModeRef;
+  pub fn CGDisplayScreenSize(display: CGDirectDisplayID) -> CGSize;
   pub fn CGDisplayCopyAllDisplayModes(
     display: CGDirectDisplayID,
     options: CFDictionaryRef,
@@ -240,6 +240,81 @@ impl MonitorHandle {
     unsafe { NSScreen::backingScaleFactor(screen) as f64 }
   }
 
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    unsafe {
+      let mode = ffi::CGDisplayCopyDisplayMode(self.0);
+      if mode.is_null() {
+        return None;
+      }
+      let refresh_rate = ffi::CGDisplayModeGetRefreshRate(mode);
+      ffi::CGDisplayModeRelease(mode);
+      if refresh_rate > 0.0 {
+        return Some((refresh_rate * 1000.0).round() as u32);
+      }
+
+      // CGDisplayModeGetRefreshRate returns 0.0 for any display that
+      // isn't a CRT
+      let mut display_link = std::ptr::null_mut();
+      if ffi::CVDisplayLinkCreateWithCGDisplay(self.0, &mut display_link) != ffi::kCVReturnSuccess {
+        return None;
+      }
+      let time = ffi::CVDisplayLinkGetNominalOutputVideoRefreshPeriod(display_link);
+      ffi::CVDisplayLinkRelease(display_link);
+      if time.flags & ffi::kCVTimeIsIndefinite != 0 || time.time_value == 0 {
+        return None;
+      }
+      Some((time.time_scale as i64 * 1000 / time.time_value) as u32)
+    }
+  }
+
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    let size = unsafe { ffi::CGDisplayScreenSize(self.0) };
+    (size.width > 0.0 && size.height > 0.0)
+      .then(|| (size.width.round() as u32, size.height.round() as u32))
+  }
+
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let screen = match self.ns_screen() {
+      Some(screen) => screen,
+      None => return (self.position(), self.size()),
+    };
+    unsafe {
+      let frame = NSScreen::visibleFrame(screen);
+      // Cocoa coordinates start at the bottom left of the main display.
+      let main_height = CGDisplayBounds(CGDisplay::main().id).size.height;
+      let scale_factor = self.scale_factor();
+      (
+        PhysicalPosition::from_logical::<_, f64>(
+          (
+            frame.origin.x as f64,
+            (main_height - frame.origin.y - frame.size.height) as f64,
+          ),
+          scale_factor,
+        ),
+        PhysicalSize::from_logical::<_, f64>(
+          (frame.size.width as f64, frame.size.height as f64),
+          scale_factor,
+        ),
+      )
+    }
+  }
+
+  pub fn stable_id(&self) -> Option<String> {
+    unsafe {
+      let uuid = ffi::CGDisplayCreateUUIDFromDisplayID(self.0);
+      if uuid.is_null() {
+        return None;
+      }
+      let string = ffi::CFUUIDCreateString(std::ptr::null(), uuid);
+      CFRelease(uuid as *const _);
+      if string.is_null() {
+        None
+      } else {
+        Some(CFString::wrap_under_create_rule(string).to_string())
+      }
+    }
+  }
+
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     let cv_refresh_rate = unsafe {
       let mut display_link = std::ptr::null_mut();
@@ -883,6 +883,21 @@ extern "C" fn flags_changed(this: &Object, _sel: Sel, ns_event: id) {
       SuperRight
     );
 
+    for (key, flag) in [
+      (KeyCode::ShiftLeft, ModifiersState::LSHIFT),
+      (KeyCode::ShiftRight, ModifiersState::RSHIFT),
+      (KeyCode::ControlLeft, ModifiersState::LCONTROL),
+      (KeyCode::ControlRight, ModifiersState::RCONTROL),
+      (KeyCode::AltLeft, ModifiersState::LALT),
+      (KeyCode::AltRight, ModifiersState::RALT),
+      (KeyCode::SuperLeft, ModifiersState::LSUPER),
+      (KeyCode::SuperRight, ModifiersState::RSUPER),
+    ] {
+      state
+        .modifiers
+        .set(flag, state.phys_modifiers.contains(&key));
+    }
+
     let window_id = WindowId(get_window_id(state.ns_window));
 
     for event in events {
@@ -34,7 +34,7 @@ use crate::{
     set_badge_label, set_progress_indicator,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
+    CenterOn, CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
     WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
   },
 };
@@ -866,6 +866,42 @@ impl UnownedWindow {
     Ok(())
   }
 
+  pub fn center_on(&self, target: CenterOn) {
+    let area = match target {
+      CenterOn::Monitor(monitor) => Some(monitor.work_area()),
+      CenterOn::Parent => self
+        .parent_area()
+        .or_else(|| self.current_monitor().map(|monitor| monitor.work_area())),
+    };
+    if let Some((position, size)) = area {
+      let outer_size = self.outer_size();
+      self.set_outer_position(
+        PhysicalPosition::new(
+          position.x + (size.width as i32 - outer_size.width as i32) / 2,
+          position.y + (size.height as i32 - outer_size.height as i32) / 2,
+        )
+        .into(),
+      );
+    }
+  }
+
+  /// Returns the frame of the parent window, if the window was added as a child of one.
+  fn parent_area(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    let parent: id = unsafe { msg_send![*self.ns_window, parentWindow] };
+    if parent == nil {
+      return None;
+    }
+    let frame = unsafe { NSWindow::frame(parent) };
+    let scale_factor = self.scale_factor();
+    let position =
+      LogicalPosition::new(frame.origin.x as f64, util::bottom_left_to_top_left(frame));
+    let size = LogicalSize::new(frame.size.width as f64, frame.size.height as f64);
+    Some((
+      position.to_physical(scale_factor),
+      size.to_physical(scale_factor),
+    ))
+  }
+
   #[inline]
   pub fn set_background_color(&self, color: Option<crate::window::RGBA>) {
     unsafe {
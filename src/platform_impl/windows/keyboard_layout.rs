@@ -266,6 +266,21 @@ impl LayoutCache {
       ModifiersState::SUPER,
       key_pressed(VK_LWIN) || key_pressed(VK_RWIN),
     );
+    mods.set(ModifiersState::LSHIFT, key_pressed(VK_LSHIFT));
+    mods.set(ModifiersState::RSHIFT, key_pressed(VK_RSHIFT));
+    // AltGr is reported as left control and right alt.
+    mods.set(
+      ModifiersState::LCONTROL,
+      key_pressed(VK_LCONTROL) && !filter_out_altgr,
+    );
+    mods.set(ModifiersState::RCONTROL, key_pressed(VK_RCONTROL));
+    mods.set(ModifiersState::LALT, key_pressed(VK_LMENU));
+    mods.set(
+      ModifiersState::RALT,
+      key_pressed(VK_RMENU) && !filter_out_altgr,
+    );
+    mods.set(ModifiersState::LSUPER, key_pressed(VK_LWIN));
+    mods.set(ModifiersState::RSUPER, key_pressed(VK_RWIN));
     mods
   }
 
@@ -219,6 +219,79 @@ impl MonitorHandle {
     dpi_to_scale_factor(get_monitor_dpi(self.hmonitor()).unwrap_or(96))
   }
 
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    let monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    unsafe {
+      let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+      let mut mode: DEVMODEW = mem::zeroed();
+      mode.dmSize = mem::size_of_val(&mode) as u16;
+      if !EnumDisplaySettingsExW(
+        device_name,
+        ENUM_CURRENT_SETTINGS,
+        &mut mode,
+        ENUM_DISPLAY_SETTINGS_FLAGS(0),
+      )
+      .as_bool()
+      {
+        return None;
+      }
+      // 0 and 1 stand for the default refresh rate of the hardware.
+      (mode.dmDisplayFrequency > 1).then(|| mode.dmDisplayFrequency * 1000)
+    }
+  }
+
+  #[inline]
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    let monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    unsafe {
+      let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+      let hdc = CreateDCW(device_name, device_name, PCWSTR::null(), None);
+      if hdc.is_invalid() {
+        return None;
+      }
+      let width = GetDeviceCaps(hdc, HORZSIZE);
+      let height = GetDeviceCaps(hdc, VERTSIZE);
+      let _ = DeleteDC(hdc);
+      (width > 0 && height > 0).then(|| (width as u32, height as u32))
+    }
+  }
+
+  #[inline]
+  pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let Ok(monitor_info) = get_monitor_info(self.hmonitor()) else {
+      return (self.position(), self.size());
+    };
+    let rect = monitor_info.monitorInfo.rcWork;
+    (
+      PhysicalPosition::new(rect.left, rect.top),
+      PhysicalSize::new(
+        (rect.right - rect.left) as u32,
+        (rect.bottom - rect.top) as u32,
+      ),
+    )
+  }
+
+  #[inline]
+  pub fn stable_id(&self) -> Option<String> {
+    // Not exposed with the `Win32_Graphics_Gdi` feature.
+    const EDD_GET_DEVICE_INTERFACE_NAME: u32 = 0x1;
+
+    let monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    unsafe {
+      let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+      let mut device: DISPLAY_DEVICEW = mem::zeroed();
+      device.cb = mem::size_of_val(&device) as u32;
+      // The device interface path of the monitor contains its EDID identifier.
+      if !EnumDisplayDevicesW(device_name, 0, &mut device, EDD_GET_DEVICE_INTERFACE_NAME).as_bool()
+      {
+        return None;
+      }
+      let id = util::wchar_ptr_to_string(PCWSTR::from_raw(device.DeviceID.as_ptr()));
+      (!id.is_empty()).then_some(id)
+    }
+  }
+
   #[inline]
   pub fn video_modes(&self) -> impl Iterator<Item = RootVideoMode> {
     // EnumDisplaySettingsExW can return duplicate values (or some of the
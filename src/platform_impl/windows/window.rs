@@ -51,7 +51,7 @@ use crate::{
     OsError, Parent, PlatformSpecificWindowBuilderAttributes, WindowId,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ProgressState, ResizeDirection, Theme,
+    CenterOn, CursorIcon, Fullscreen, ProgressBarState, ProgressState, ResizeDirection, Theme,
     UserAttentionType, WindowAttributes, WindowSizeConstraints, RGBA,
   },
 };
@@ -1107,6 +1107,51 @@ impl Window {
       );
     }
   }
+
+  pub fn center_on(&self, target: CenterOn) {
+    let area = match target {
+      CenterOn::Monitor(monitor) => Some(monitor.work_area()),
+      CenterOn::Parent => self
+        .parent_area()
+        .or_else(|| self.current_monitor().map(|monitor| monitor.work_area())),
+    };
+    if let Some((position, size)) = area {
+      let outer_size = self.outer_size();
+      self.set_outer_position(
+        PhysicalPosition::new(
+          position.x + (size.width as i32 - outer_size.width as i32) / 2,
+          position.y + (size.height as i32 - outer_size.height as i32) / 2,
+        )
+        .into(),
+      );
+    }
+  }
+
+  /// Returns the client area of the parent of a child window, or the bounds of the owner of a
+  /// top-level window, in the coordinates the window is positioned in.
+  fn parent_area(&self) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    let is_child = self
+      .window_state
+      .lock()
+      .window_flags()
+      .contains(WindowFlags::CHILD);
+    let rect = unsafe {
+      if is_child {
+        let mut rect = RECT::default();
+        GetClientRect(GetParent(self.hwnd()).ok()?, &mut rect).ok()?;
+        rect
+      } else {
+        util::get_window_rect(GetWindow(self.hwnd(), GW_OWNER).ok()?)?
+      }
+    };
+    Some((
+      PhysicalPosition::new(rect.left, rect.top),
+      PhysicalSize::new(
+        (rect.right - rect.left) as u32,
+        (rect.bottom - rect.top) as u32,
+      ),
+    ))
+  }
 }
 
 impl Drop for Window {
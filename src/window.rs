@@ -20,11 +20,11 @@ pub use crate::icon::{BadIcon, Icon};
 pub enum ProgressState {
   None,
   Normal,
-  /// **Treated as Normal in linux and macOS**
+  /// **Treated as Normal in macOS, hides the progress in linux**
   Indeterminate,
   /// **Treated as Normal in linux**
   Paused,
-  /// **Treated as Normal in linux**
+  /// **Shown as urgent in linux**
   Error,
 }
 
@@ -33,7 +33,7 @@ pub struct ProgressBarState {
   pub state: Option<ProgressState>,
   /// The progress bar progress. This can be a value ranging from `0` to `100`
   pub progress: Option<u64>,
-  /// The `.desktop` filename with the Unity desktop window manager, for example `myapp.desktop` **Linux Only**
+  /// The `.desktop` filename of the application, for example `myapp.desktop` **Linux Only**
   pub desktop_filename: Option<String>,
 }
 
@@ -874,7 +874,8 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux(X11):** Depends on the window manager honoring the Motif hints.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_minimizable(&self, minimizable: bool) {
     self.window.set_minimizable(minimizable)
@@ -885,7 +886,9 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** Disables the "zoom" button in the window titlebar, which is also used to enter fullscreen mode.
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux:** A window that is not resizable is never maximizable.
+  /// - **Linux(X11):** Depends on the window manager honoring the Motif hints.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_maximizable(&self, maximizable: bool) {
     self.window.set_maximizable(maximizable)
@@ -967,7 +970,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_minimizable(&self) -> bool {
     self.window.is_minimizable()
@@ -977,7 +980,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_maximizable(&self) -> bool {
     self.window.is_maximizable()
@@ -1020,7 +1023,8 @@ impl Window {
   ///   The dock and the menu bar are always disabled in fullscreen mode.
   /// - **iOS:** Can only be called on the main thread.
   /// - **Windows:** Screen saver is disabled in fullscreen mode.
-  /// - **Linux:** The window will only fullscreen to current monitor no matter which enum variant.
+  /// - **Linux:** `Fullscreen::Exclusive` is only supported on X11, the previous video mode is
+  ///   restored when leaving fullscreen, destroying the window or exiting the event loop.
   /// - **Android:** Unsupported.
   #[inline]
   pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
@@ -1099,7 +1103,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / macOS**: Unlike windows, progress bar is app-wide and not specific to this window. Only supported desktop environments with `libunity` (e.g. GNOME).
+  /// - **Linux / macOS**: Unlike windows, progress bar is app-wide and not specific to this window. Only supported by desktop environments implementing the `com.canonical.Unity.LauncherEntry` D-Bus API (e.g. KDE Plasma, Unity or GNOME with Dash to Dock).
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_progress_bar(&self, _progress: ProgressBarState) {
@@ -1182,6 +1186,22 @@ impl Window {
     self.window.set_visible_on_all_workspaces(visible)
   }
 
+  /// Moves the window to the center of its parent or of a monitor.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** [`CenterOn::Parent`] centers on the window set with
+  ///   `WindowBuilderExtUnix::with_transient_for`. Windows created with it are centered on it
+  ///   by default.
+  /// - **Windows:** [`CenterOn::Parent`] centers on the owner window, or in the client area of
+  ///   the parent window for child windows.
+  /// - **macOS:** [`CenterOn::Parent`] centers on the parent window.
+  /// - **iOS / Android / Linux(Wayland):** Unsupported.
+  #[inline]
+  pub fn center_on(&self, target: CenterOn) {
+    self.window.center_on(target)
+  }
+
   /// Sets the window background color.
   ///
   /// ## Platform-specific:
@@ -1210,6 +1230,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
+  /// - **Linux(Wayland):** Always returns an [`ExternalError::Os`] of kind `OsErrorKind::WaylandUnsupported`.
   /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
   #[inline]
   pub fn set_cursor_position<P: Into<Position>>(&self, position: P) -> Result<(), ExternalError> {
@@ -1224,6 +1245,9 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** This locks the cursor in a fixed location, which looks visually awkward.
+  /// - **Linux(X11):** This confines the cursor to the window.
+  /// - **Linux(Wayland):** This locks the cursor in a fixed location. Returns an [`ExternalError::Os`] of kind
+  ///   `OsErrorKind::WaylandUnsupported` if the compositor doesn't support the pointer constraints protocol.
   /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
   #[inline]
   pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
@@ -1289,7 +1313,9 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **iOS / Android / Linux(Wayland)**: Unsupported, returns `0,0`.
+  /// - **iOS / Android**: Unsupported, returns `0,0`.
+  /// - **Linux(Wayland)**: Returns the last known position relative to the window, or
+  ///   [`ExternalError::NotSupported`] if the cursor is outside of it.
   #[inline]
   pub fn cursor_position(&self) -> Result<PhysicalPosition<f64>, ExternalError> {
     self.window.cursor_position()
@@ -1467,6 +1493,16 @@ pub enum Fullscreen {
   Borderless(Option<MonitorHandle>),
 }
 
+/// What to center a window on, see [`Window::center_on`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CenterOn {
+  /// The parent of the window, or its current monitor if it has none.
+  Parent,
+  /// The work area of a monitor.
+  Monitor(MonitorHandle),
+}
+
 #[non_exhaustive]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub enum Theme {
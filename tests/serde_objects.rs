@@ -6,8 +6,9 @@
 
 use serde::{Deserialize, Serialize};
 use tao::{
+  accelerator::Accelerator,
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
-  event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
+  event::{ElementState, MonitorEvent, MouseButton, MouseScrollDelta, TouchPhase},
   keyboard::{Key, KeyCode, KeyLocation, ModifiersState},
   window::CursorIcon,
 };
@@ -26,10 +27,12 @@ fn events_serde() {
   needs_serde::<ElementState>();
   needs_serde::<MouseButton>();
   needs_serde::<MouseScrollDelta>();
+  needs_serde::<MonitorEvent>();
   needs_serde::<Key>();
   needs_serde::<KeyCode>();
   needs_serde::<KeyLocation>();
   needs_serde::<ModifiersState>();
+  needs_serde::<Accelerator>();
 }
 
 #[test]